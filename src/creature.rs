@@ -1,7 +1,32 @@
 use anyhow::Context;
 
-use crate::{dndbeyond::creature_list::CreatureListing, utility::NoSuchElement};
-use std::{path::PathBuf, str::FromStr};
+use crate::{
+	dndbeyond::creature_list::CreatureListing,
+	selectors::{CreatureSelectors, Selectors},
+	utility::{ParseError, Scope},
+};
+use std::path::PathBuf;
+
+pub mod ability;
+use ability::{Ability, AbilityScore, AbilityScores};
+pub mod tidbit;
+use tidbit::{Challenge, SavingThrow, Senses, Skill};
+pub mod description;
+use description::{BlockKind, DescriptionBlock, RichText};
+pub mod attack;
+pub mod dice;
+use dice::DiceExpression;
+pub mod challenge_rating;
+use challenge_rating::ChallengeRating;
+pub mod diagnostic;
+use diagnostic::{Diagnostic, FieldParser, ParseMode};
+pub mod speed;
+use speed::Speed;
+pub mod armor_class;
+use armor_class::ArmorClass;
+pub mod recharge;
+pub mod validation;
+use validation::DataWarning;
 
 fn strip_whitespace(text: String) -> anyhow::Result<String> {
 	let strip_whitespace_r = regex::Regex::new(r"^[ \n\t]+(.*?)[ \t\n]+$")?;
@@ -13,37 +38,94 @@ fn strip_whitespace(text: String) -> anyhow::Result<String> {
 	Ok(text)
 }
 
-#[derive(Clone)]
-pub struct DiceRoll(u32, u32, Option<i32>);
-impl std::fmt::Debug for DiceRoll {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "DiceRoll({})", self)
-	}
+/// Returns the text content of an element, with all runs of whitespace collapsed to a single space.
+fn collapse_text(element: &scraper::ElementRef<'_>) -> String {
+	let text = element.text().collect::<String>();
+	text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
-impl std::fmt::Display for DiceRoll {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		match self.2.as_ref() {
-			Some(bonus) => write!(f, "{}d{}{:+}", self.0, self.1, bonus),
-			None => write!(f, "{}d{}", self.0, self.1),
+
+/// Splits the text at each of the separators which are not within parentheses,
+/// trimming each part and skipping any which are empty.
+fn split_outside_parentheses<'a>(text: &'a str, separators: &[char]) -> Vec<&'a str> {
+	let mut parts = Vec::new();
+	let mut depth = 0usize;
+	let mut start = 0;
+	for (idx, c) in text.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => depth = depth.saturating_sub(1),
+			c if depth == 0 && separators.contains(&c) => {
+				parts.push(text[start..idx].trim());
+				start = idx + c.len_utf8();
+			}
+			_ => {}
 		}
 	}
+	parts.push(text[start..].trim());
+	parts.retain(|part| !part.is_empty());
+	parts
 }
 
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
 pub struct Creature {
 	name: String,
 	source_book: String,
 	url: PathBuf,
-	challenge_rating: Option<usize>,
+	challenge_rating: Option<ChallengeRating>,
 	kind: String,
 	size: String,
 	alignment: String,
-	armor_class: (u32, Option<String>),
-	hit_points: (u32, Option<DiceRoll>),
-	speeds: Vec<(u32, Option<String>, Option<String>)>,
+	/// Missing only if the creature was parsed leniently and its armor class could not be parsed.
+	armor_class: Option<ArmorClass>,
+	/// The hit points, and the dice they are rolled with if the stat block has them.
+	/// Missing only if the creature was parsed leniently and its hit points could not be parsed.
+	hit_points: Option<(u32, Option<DiceExpression>)>,
+	speeds: Vec<Speed>,
+	/// Missing only if the creature was parsed leniently and its ability scores could not be parsed.
+	ability_scores: Option<AbilityScores>,
+	saving_throws: Vec<SavingThrow>,
+	skills: Vec<Skill>,
+	damage_vulnerabilities: Vec<String>,
+	damage_resistances: Vec<String>,
+	damage_immunities: Vec<String>,
+	condition_immunities: Vec<String>,
+	senses: Senses,
+	languages: Vec<String>,
+	challenge: Option<Challenge>,
+	proficiency_bonus: Option<i32>,
+	traits: DescriptionBlock,
+	actions: DescriptionBlock,
+	bonus_actions: DescriptionBlock,
+	reactions: DescriptionBlock,
+	legendary_actions: DescriptionBlock,
+	mythic_actions: DescriptionBlock,
+	/// The fields which could not be parsed, and were left empty, if the creature was parsed leniently.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	diagnostics: Vec<Diagnostic>,
+	/// The printed values (averages and ability modifiers) which disagree with the rest of the stat block,
+	/// likely typos on the page.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	warnings: Vec<DataWarning>,
+	/// The listing the creature was fetched from, kept so later crawls can tell if the listing has changed.
+	#[serde(skip)]
+	listing: CreatureListing,
 }
 impl Creature {
+	/// The version of the shape the creature is serialized with (as stored in the database and exported).
+	/// Bump it whenever that shape changes, or what is derived from the page changes (like the `warnings`),
+	/// so creatures saved by an older version are fetched again instead of being exported out of date.
+	pub const DATA_VERSION: i32 = 5;
+
+	/// Parses the creature's page strictly, failing if any field of the stat block cannot be parsed.
 	pub fn parse(listing: CreatureListing, html: String) -> anyhow::Result<Self> {
+		Self::parse_with_mode(listing, html, ParseMode::Strict)
+	}
+
+	pub fn parse_with_mode(
+		listing: CreatureListing,
+		html: String,
+		mode: ParseMode,
+	) -> anyhow::Result<Self> {
 		let CreatureListing {
 			name: _,
 			source_book,
@@ -51,120 +133,325 @@ impl Creature {
 			challenge_rating,
 			kind: _,
 			size: _,
-		} = listing;
+		} = listing.clone();
+		let page_url = listing.url().to_string_lossy().to_string();
 		let document = scraper::Html::parse_document(&html);
+		let mut fields = FieldParser::new(mode);
 
-		let page = CreaturePage::from_document(&document);
+		let page = CreaturePage::from_document(&document, &page_url)?;
 
-		let stat_block = page.stat_block();
+		let stat_block = page.stat_block()?;
 		let (name, url, size, kind, alignment) = {
-			let header = stat_block.header();
+			let header = stat_block.header()?;
 			let (name, url) = {
-				let name_link = header.name_link();
+				let name_link = header.name_link()?;
 				let name = name_link.name()?;
 				let url = name_link.url()?;
 				(name, url)
 			};
-			let (size, kind, alignment) = header.meta().expand()?;
+			let meta = header
+				.meta()
+				.map_err(anyhow::Error::from)
+				.and_then(|meta| meta.expand());
+			let (size, kind, alignment) =
+				fields.parse_or_default("size, type, and alignment", meta)?;
 			(name, url, size, kind, alignment)
 		};
 		let (armor_class, hit_points, speeds) = {
-			let attributes = stat_block.attributes()?;
-			let armor_class = attributes.armor_class()?;
-			let hit_points = attributes.hit_points()?;
-			let speeds = attributes.speeds()?;
+			let attributes = fields.parse("attributes", stat_block.attributes())?;
+			let attributes = attributes.as_ref();
+			let armor_class = fields.parse_from(attributes, "armor class", |attributes| {
+				attributes.armor_class().map(Some)
+			})?;
+			let hit_points = fields.parse_from(attributes, "hit points", |attributes| {
+				attributes.hit_points().map(Some)
+			})?;
+			let speeds = fields.parse_from(attributes, "speed", Attributes::speeds)?;
 			(armor_class, hit_points, speeds)
 		};
+		let ability_scores = stat_block
+			.stats()
+			.map_err(anyhow::Error::from)
+			.and_then(|stats| stats.ability_scores());
+		let ability_scores = fields.parse("ability scores", ability_scores)?;
+		let tidbits = fields.parse("tidbits", stat_block.tidbits())?;
+		let tidbits = tidbits.as_ref();
+		let mut traits = DescriptionBlock::default();
+		let mut actions = DescriptionBlock::default();
+		let mut bonus_actions = DescriptionBlock::default();
+		let mut reactions = DescriptionBlock::default();
+		let mut legendary_actions = DescriptionBlock::default();
+		let mut mythic_actions = DescriptionBlock::default();
+		for block in stat_block.description_blocks() {
+			let heading = block.heading();
+			let Some(kind) = BlockKind::from_heading(heading.as_deref()) else {
+				log::warn!("Unknown description block {heading:?} for creature {name:?}");
+				continue;
+			};
+			let target = match kind {
+				BlockKind::Traits => &mut traits,
+				BlockKind::Actions => &mut actions,
+				BlockKind::BonusActions => &mut bonus_actions,
+				BlockKind::Reactions => &mut reactions,
+				BlockKind::LegendaryActions => &mut legendary_actions,
+				BlockKind::MythicActions => &mut mythic_actions,
+			};
+			for (entry_name, text) in block.paragraphs() {
+				target.push(entry_name, text);
+			}
+		}
+		for (kind, block) in [
+			(BlockKind::Actions, &mut actions),
+			(BlockKind::BonusActions, &mut bonus_actions),
+			(BlockKind::Reactions, &mut reactions),
+			(BlockKind::LegendaryActions, &mut legendary_actions),
+			(BlockKind::MythicActions, &mut mythic_actions),
+		] {
+			fields.parse_or_default(&format!("{kind} entries"), block.parse_entries())?;
+		}
 
-		let creature = Self {
+		let challenge = fields.parse_from(tidbits, "challenge", Tidbits::challenge)?;
+		let saving_throws = fields.parse_from(tidbits, "saving throws", Tidbits::saving_throws)?;
+		let skills = fields.parse_from(tidbits, "skills", Tidbits::skills)?;
+		let senses = fields.parse_from(tidbits, "senses", Tidbits::senses)?;
+		let proficiency_bonus =
+			fields.parse_from(tidbits, "proficiency bonus", Tidbits::proficiency_bonus)?;
+		let diagnostics = fields.into_diagnostics();
+		for diagnostic in diagnostics.iter() {
+			log::warn!("Left {name:?} partially parsed, {diagnostic}");
+		}
+		let mut creature = Self {
 			name,
 			source_book,
 			url,
-			challenge_rating,
+			// Prefer the listing's rating, but fall back to the stat block if the listing didn't have one.
+			challenge_rating: challenge_rating.or(challenge.as_ref().map(|c| *c.rating())),
 			kind,
 			size,
 			alignment,
 			armor_class,
 			hit_points,
 			speeds,
+			ability_scores,
+			saving_throws,
+			skills,
+			damage_vulnerabilities: tidbits
+				.map(Tidbits::damage_vulnerabilities)
+				.unwrap_or_default(),
+			damage_resistances: tidbits.map(Tidbits::damage_resistances).unwrap_or_default(),
+			damage_immunities: tidbits.map(Tidbits::damage_immunities).unwrap_or_default(),
+			condition_immunities: tidbits
+				.map(Tidbits::condition_immunities)
+				.unwrap_or_default(),
+			senses,
+			languages: tidbits.map(Tidbits::languages).unwrap_or_default(),
+			challenge,
+			proficiency_bonus,
+			traits,
+			actions,
+			bonus_actions,
+			reactions,
+			legendary_actions,
+			mythic_actions,
+			diagnostics,
+			warnings: Vec::new(),
+			listing,
 		};
+		creature.warnings = validation::check(&creature)?;
+		for warning in creature.warnings.iter() {
+			log::warn!("Possible typo in {:?}, {warning}", creature.name);
+		}
 		log::debug!("{creature:?}");
 		Ok(creature)
 	}
+
+	pub fn name(&self) -> &String {
+		&self.name
+	}
+
+	pub fn source_book(&self) -> &String {
+		&self.source_book
+	}
+
+	pub fn url(&self) -> &PathBuf {
+		&self.url
+	}
+
+	pub fn listing(&self) -> &CreatureListing {
+		&self.listing
+	}
+
+	pub fn challenge_rating(&self) -> Option<ChallengeRating> {
+		self.challenge_rating
+	}
+
+	pub fn kind(&self) -> &String {
+		&self.kind
+	}
+
+	pub fn size(&self) -> &String {
+		&self.size
+	}
+
+	pub fn alignment(&self) -> &String {
+		&self.alignment
+	}
+
+	pub fn armor_class(&self) -> Option<&ArmorClass> {
+		self.armor_class.as_ref()
+	}
+
+	pub fn hit_points(&self) -> Option<&(u32, Option<DiceExpression>)> {
+		self.hit_points.as_ref()
+	}
+
+	pub fn speeds(&self) -> &Vec<Speed> {
+		&self.speeds
+	}
+
+	pub fn ability_scores(&self) -> Option<&AbilityScores> {
+		self.ability_scores.as_ref()
+	}
+
+	pub fn saving_throws(&self) -> &Vec<SavingThrow> {
+		&self.saving_throws
+	}
+
+	pub fn skills(&self) -> &Vec<Skill> {
+		&self.skills
+	}
+
+	pub fn damage_vulnerabilities(&self) -> &Vec<String> {
+		&self.damage_vulnerabilities
+	}
+
+	pub fn damage_resistances(&self) -> &Vec<String> {
+		&self.damage_resistances
+	}
+
+	pub fn damage_immunities(&self) -> &Vec<String> {
+		&self.damage_immunities
+	}
+
+	pub fn condition_immunities(&self) -> &Vec<String> {
+		&self.condition_immunities
+	}
+
+	pub fn senses(&self) -> &Senses {
+		&self.senses
+	}
+
+	pub fn languages(&self) -> &Vec<String> {
+		&self.languages
+	}
+
+	pub fn challenge(&self) -> Option<&Challenge> {
+		self.challenge.as_ref()
+	}
+
+	pub fn proficiency_bonus(&self) -> Option<i32> {
+		self.proficiency_bonus
+	}
+
+	pub fn diagnostics(&self) -> &Vec<Diagnostic> {
+		&self.diagnostics
+	}
+
+	pub fn warnings(&self) -> &Vec<DataWarning> {
+		&self.warnings
+	}
+
+	/// Returns each of the description blocks (traits, actions, etc) in the order they appear in the stat block.
+	pub fn description_blocks(&self) -> [(BlockKind, &DescriptionBlock); 6] {
+		[
+			(BlockKind::Traits, &self.traits),
+			(BlockKind::Actions, &self.actions),
+			(BlockKind::BonusActions, &self.bonus_actions),
+			(BlockKind::Reactions, &self.reactions),
+			(BlockKind::LegendaryActions, &self.legendary_actions),
+			(BlockKind::MythicActions, &self.mythic_actions),
+		]
+	}
+}
+
+/// The selectors for a creature's page, which may have been overridden by the user (see [`Selectors`]).
+fn selectors() -> &'static CreatureSelectors {
+	&Selectors::get().creature
 }
 
-struct CreaturePage<'doc>(scraper::ElementRef<'doc>);
+struct CreaturePage<'doc>(Scope<'doc>);
 impl<'doc> CreaturePage<'doc> {
-	pub fn from_document(document: &'doc scraper::Html) -> CreaturePage<'doc> {
-		let s_primary_content = scraper::Selector::parse(
-			r#"body > #site > #site-main > .container > #content > .primary-content"#,
-		)
-		.unwrap();
-		let s_content =
-			scraper::Selector::parse(r#".monster-details > div > .detail-content"#).unwrap();
-		let primary_content = document.select(&s_primary_content).next().unwrap();
-		let content = primary_content.select(&s_content).next().unwrap();
-		Self(content)
-	}
-
-	pub fn stat_block(&self) -> StatBlock<'doc> {
-		let s_stat_block = scraper::Selector::parse(r#".mon-stat-block"#).unwrap();
-		StatBlock(self.0.select(&s_stat_block).next().unwrap())
+	pub fn from_document(
+		document: &'doc scraper::Html,
+		url: &'doc str,
+	) -> Result<CreaturePage<'doc>, ParseError> {
+		let primary_content =
+			Scope::root(document, url).select(&selectors().page_content, "page content")?;
+		let content = primary_content.select(&selectors().details, "creature details")?;
+		Ok(Self(content))
+	}
+
+	pub fn stat_block(&self) -> Result<StatBlock<'doc>, ParseError> {
+		Ok(StatBlock(
+			self.0.select(&selectors().stat_block, "stat block")?,
+		))
 	}
 }
 
-struct StatBlock<'doc>(scraper::ElementRef<'doc>);
+struct StatBlock<'doc>(Scope<'doc>);
 impl<'doc> StatBlock<'doc> {
-	pub fn header(&self) -> StatBlockHeader<'doc> {
-		let s_header = scraper::Selector::parse(r#".mon-stat-block__header"#).unwrap();
-		StatBlockHeader(self.0.select(&s_header).next().unwrap())
+	pub fn header(&self) -> Result<StatBlockHeader<'doc>, ParseError> {
+		Ok(StatBlockHeader(
+			self.0.select(&selectors().header, "stat block header")?,
+		))
 	}
 
 	pub fn attributes(&self) -> anyhow::Result<Attributes> {
-		let s_attributes = scraper::Selector::parse(r#".mon-stat-block__attributes"#).unwrap();
-		Attributes::from(self.0.select(&s_attributes).next().unwrap())
+		Attributes::from(self.0.select(&selectors().attributes, "attributes")?)
+	}
+
+	pub fn stats(&self) -> Result<Stats<'doc>, ParseError> {
+		Ok(Stats(self.0.select(&selectors().stats, "ability scores")?))
 	}
 
-	pub fn stats(&self) -> Stats<'doc> {
-		let s_stats = scraper::Selector::parse(r#".mon-stat-block__stat-block"#).unwrap();
-		Stats(self.0.select(&s_stats).next().unwrap())
+	pub fn tidbits(&self) -> anyhow::Result<Tidbits> {
+		Tidbits::from(self.0.select(&selectors().tidbits, "tidbits")?)
 	}
 
-	pub fn tidbits(&self) -> Tidbits<'doc> {
-		let s_tidbits = scraper::Selector::parse(r#".mon-stat-block__tidbits"#).unwrap();
-		Tidbits(self.0.select(&s_tidbits).next().unwrap())
+	pub fn description_blocks(&self) -> Vec<DescriptionBlockHtml<'doc>> {
+		let blocks = self.0.select_all(&selectors().description_block);
+		blocks.into_iter().map(DescriptionBlockHtml).collect()
 	}
 }
 
-struct StatBlockHeader<'doc>(scraper::ElementRef<'doc>);
+struct StatBlockHeader<'doc>(Scope<'doc>);
 impl<'doc> StatBlockHeader<'doc> {
-	pub fn name_link(&self) -> StatBlockHeaderNameLink<'doc> {
-		let selector =
-			scraper::Selector::parse(r#".mon-stat-block__name > a.mon-stat-block__name-link"#)
-				.unwrap();
-		StatBlockHeaderNameLink(self.0.select(&selector).next().unwrap())
+	pub fn name_link(&self) -> Result<StatBlockHeaderNameLink<'doc>, ParseError> {
+		Ok(StatBlockHeaderNameLink(
+			self.0.select(&selectors().name_link, "creature name")?,
+		))
 	}
 
-	pub fn meta(&self) -> StatBlockHeaderMeta<'doc> {
-		let selector = scraper::Selector::parse(r#".mon-stat-block__meta"#).unwrap();
-		StatBlockHeaderMeta(self.0.select(&selector).next().unwrap())
+	pub fn meta(&self) -> Result<StatBlockHeaderMeta<'doc>, ParseError> {
+		Ok(StatBlockHeaderMeta(
+			self.0
+				.select(&selectors().meta, "size, type, and alignment")?,
+		))
 	}
 }
 
-struct StatBlockHeaderNameLink<'doc>(scraper::ElementRef<'doc>);
+struct StatBlockHeaderNameLink<'doc>(Scope<'doc>);
 impl<'doc> StatBlockHeaderNameLink<'doc> {
 	pub fn name(&self) -> anyhow::Result<String> {
 		Ok(strip_whitespace(self.0.inner_html())?)
 	}
 
 	pub fn url(&self) -> anyhow::Result<PathBuf> {
-		Ok(PathBuf::from_str(
-			self.0.value().attr("href").ok_or(NoSuchElement)?,
-		)?)
+		Ok(PathBuf::from(self.0.attr("href", "creature url")?))
 	}
 }
 
-struct StatBlockHeaderMeta<'doc>(scraper::ElementRef<'doc>);
+struct StatBlockHeaderMeta<'doc>(Scope<'doc>);
 impl<'doc> StatBlockHeaderMeta<'doc> {
 	pub fn expand(&self) -> anyhow::Result<(String, String, String)> {
 		use verbal_expr::Expression::Verex as Group;
@@ -178,24 +465,20 @@ impl<'doc> StatBlockHeaderMeta<'doc> {
 		let text = self.0.inner_html();
 		let captures = regex
 			.captures(&text)
-			.ok_or(NoSuchElement)
-			.context("parse stat-block meta text")?;
+			.ok_or_else(|| self.0.invalid_text("size, type, and alignment", &text))?;
 		let size = captures
 			.get(1)
-			.ok_or(NoSuchElement)
-			.context("parse creature size")?
+			.ok_or_else(|| self.0.invalid_text("creature size", &text))?
 			.as_str()
 			.to_owned();
 		let kind = captures
 			.get(2)
-			.ok_or(NoSuchElement)
-			.context("parse creature type")?
+			.ok_or_else(|| self.0.invalid_text("creature type", &text))?
 			.as_str()
 			.to_owned();
 		let alignment = captures
 			.get(3)
-			.ok_or(NoSuchElement)
-			.context("parse alignment")?
+			.ok_or_else(|| self.0.invalid_text("alignment", &text))?
 			.as_str()
 			.to_owned();
 		Ok((size, kind, alignment))
@@ -220,46 +503,38 @@ struct Attributes {
 	speed: String,
 }
 impl Attributes {
-	fn from<'doc>(html: scraper::ElementRef<'doc>) -> anyhow::Result<Self> {
-		let s_attr = scraper::Selector::parse(r#".mon-stat-block__attribute"#).unwrap();
-		let s_label = scraper::Selector::parse(r#".mon-stat-block__attribute-label"#).unwrap();
-		let s_value = scraper::Selector::parse(r#".mon-stat-block__attribute-value"#).unwrap();
-		let s_data = scraper::Selector::parse(r#".mon-stat-block__attribute-data"#).unwrap();
-		let s_data_value =
-			scraper::Selector::parse(r#".mon-stat-block__attribute-data-value"#).unwrap();
-		let s_data_extra =
-			scraper::Selector::parse(r#".mon-stat-block__attribute-data-extra"#).unwrap();
-
+	fn from(html: Scope<'_>) -> anyhow::Result<Self> {
 		let mut armor_class = (String::default(), None);
 		let mut hit_points = (String::default(), None);
 		let mut speed = String::default();
-		for element in html.select(&s_attr) {
-			let label = element.select(&s_label).next().unwrap();
+		for element in html.select_all(&selectors().attribute) {
+			let label = element.select(&selectors().attribute_label, "attribute label")?;
 			match label.inner_html().as_str() {
 				"Armor Class" => {
-					let value = element.select(&s_value).next().unwrap();
-					let datum = value.select(&s_data_value).next().unwrap();
-					let ac = datum.inner_html();
-					let source = match value.select(&s_data_extra).next() {
-						Some(extra) => Some(extra.inner_html()),
-						None => None,
-					};
+					let value = element.select(&selectors().attribute_value, "armor class")?;
+					let ac = value
+						.select(&selectors().attribute_data_value, "armor class")?
+						.inner_html();
+					let source = value
+						.select_optional(&selectors().attribute_data_extra)
+						.map(|extra| extra.inner_html());
 					armor_class = (ac, source);
 				}
 				"Hit Points" => {
-					let data = element.select(&s_data).next().unwrap();
-					let datum = data.select(&s_data_value).next().unwrap();
-					let hp = datum.inner_html();
-					let dice = match data.select(&s_data_extra).next() {
-						Some(extra) => Some(extra.inner_html()),
-						None => None,
-					};
+					let data = element.select(&selectors().attribute_data, "hit points")?;
+					let hp = data
+						.select(&selectors().attribute_data_value, "hit points")?
+						.inner_html();
+					let dice = data
+						.select_optional(&selectors().attribute_data_extra)
+						.map(|extra| extra.inner_html());
 					hit_points = (hp, dice);
 				}
 				"Speed" => {
-					let data = element.select(&s_data).next().unwrap();
-					let values_html = data.select(&s_data_value).next().unwrap();
-					speed = values_html.inner_html();
+					let data = element.select(&selectors().attribute_data, "speed")?;
+					speed = data
+						.select(&selectors().attribute_data_value, "speed")?
+						.inner_html();
 				}
 				_ => {}
 			}
@@ -271,103 +546,226 @@ impl Attributes {
 		})
 	}
 
-	pub fn armor_class(&self) -> anyhow::Result<(u32, Option<String>)> {
-		Ok((
-			strip_whitespace(self.armor_class.0.clone())?.parse::<u32>()?,
-			match &self.armor_class.1 {
-				Some(text) => Some(strip_whitespace(text.clone())?),
-				None => None,
-			},
-		))
+	pub fn armor_class(&self) -> anyhow::Result<ArmorClass> {
+		// The sources and alternate values are usually separate from the value,
+		// but some pages have the whole armor class in the value.
+		let mut text = strip_whitespace(self.armor_class.0.clone())?;
+		if let Some(extra) = &self.armor_class.1 {
+			text = format!("{text} {}", strip_whitespace(extra.clone())?);
+		}
+		text.parse::<ArmorClass>()
 	}
 
-	pub fn hit_points(&self) -> anyhow::Result<(u32, Option<DiceRoll>)> {
-		use verbal_expr::Expression::Verex as Group;
-		let dice_regex = verbal_expr::find("(")
-			.capture_expr(Group(&verbal_expr::digit().repeat_once_or_more()))
-			.then("d")
-			.capture_expr(Group(&verbal_expr::digit().repeat_once_or_more()))
-			.maybe_expr(Group(
-				&verbal_expr::find(" ")
-					.capture_expr(Group(&verbal_expr::find("+").or_find("-")))
-					.then(" ")
-					.capture_expr(Group(&verbal_expr::digit().repeat_once_or_more())),
-			))
-			.then(")")
-			.compile()?;
-		Ok((
-			strip_whitespace(self.hit_points.0.clone())?.parse::<u32>()?,
-			match &self.hit_points.1 {
-				Some(text) => {
-					let text = strip_whitespace(text.clone())?;
-					match dice_regex.captures(&text) {
-						Some(capture) => {
-							let die_count = capture
-								.get(1)
-								.map(|item| item.as_str().parse::<u32>().ok())
-								.flatten()
-								.unwrap_or(0);
-							let die_kind = capture
-								.get(2)
-								.map(|item| item.as_str().parse::<u32>().ok())
-								.flatten()
-								.unwrap_or(0);
-							let bonus_sign = capture
-								.get(3)
-								.map(|item| match item.as_str() {
-									"+" => Some(1),
-									"-" => Some(-1),
-									_ => None,
-								})
-								.flatten()
-								.unwrap_or(0);
-							let bonus = capture
-								.get(4)
-								.map(|item| item.as_str().parse::<i32>().ok())
-								.flatten();
-							Some(DiceRoll(die_count, die_kind, bonus.map(|v| v * bonus_sign)))
-						}
-						None => None,
-					}
-				}
-				None => None,
-			},
-		))
+	pub fn hit_points(&self) -> anyhow::Result<(u32, Option<DiceExpression>)> {
+		let hit_points = strip_whitespace(self.hit_points.0.clone())?.parse::<u32>()?;
+		let dice = match &self.hit_points.1 {
+			Some(text) => {
+				let text = strip_whitespace(text.clone())?;
+				let text = text
+					.strip_prefix('(')
+					.and_then(|text| text.strip_suffix(')'))
+					.with_context(|| format!("parse hit dice {text:?}"))?;
+				Some(text.parse::<DiceExpression>()?)
+			}
+			None => None,
+		};
+		Ok((hit_points, dice))
 	}
 
-	pub fn speeds(&self) -> anyhow::Result<Vec<(u32, Option<String>, Option<String>)>> {
-		use verbal_expr::Expression::Verex as Group;
+	pub fn speeds(&self) -> anyhow::Result<Vec<Speed>> {
+		Speed::parse_all(&strip_whitespace(self.speed.clone())?)
+	}
+}
 
-		let speed_regex = verbal_expr::maybe_expr(Group(
-			&verbal_expr::capture_expr(Group(&verbal_expr::anything_but(" "))).then(" "),
-		))
-		.capture_expr(Group(&verbal_expr::digit().repeat_once_or_more()))
-		.then(" ft.")
-		.maybe_expr(Group(
-			&verbal_expr::find(" (")
-				.capture_expr(Group(&verbal_expr::word()))
-				.find(")"),
-		))
-		.compile()?;
-
-		let all_speeds = strip_whitespace(self.speed.clone())?;
-		Ok(all_speeds
-			.split(",")
-			.filter_map(|speed_str| speed_regex.captures(&speed_str))
-			.map(|capture| {
-				let speed_type = capture.get(1).map(|item| item.as_str().to_owned());
-				let distance = capture
-					.get(2)
-					.map(|item| item.as_str().parse::<u32>().ok())
-					.flatten()
-					.unwrap_or(0);
-				let subtype = capture.get(3).map(|item| item.as_str().to_owned());
-				(distance, speed_type, subtype)
-			})
-			.collect::<Vec<_>>())
+struct Stats<'doc>(Scope<'doc>);
+impl<'doc> Stats<'doc> {
+	pub fn ability_scores(&self) -> anyhow::Result<AbilityScores> {
+		let mut scores = Vec::with_capacity(6);
+		let stats = self.0.select_all(&selectors().ability);
+		for element in stats {
+			let heading = element
+				.select(&selectors().ability_heading, "ability heading")?
+				.inner_html();
+			let ability = heading.parse::<Ability>()?;
+			let score = element
+				.select(&selectors().ability_score, "ability score")
+				.with_context(|| format!("parse {ability} score"))?
+				.inner_html();
+			let score = score
+				.trim()
+				.parse::<u32>()
+				.with_context(|| format!("parse {ability} score {score:?}"))?;
+			let modifier = element
+				.select(&selectors().ability_modifier, "ability modifier")
+				.with_context(|| format!("parse {ability} modifier"))?
+				.inner_html();
+			let modifier = parse_modifier(&modifier)
+				.with_context(|| format!("parse {ability} modifier {modifier:?}"))?;
+			scores.push((ability, AbilityScore::new(score, modifier)));
+		}
+		Ok(AbilityScores::try_from(scores)?)
 	}
 }
 
-struct Stats<'doc>(scraper::ElementRef<'doc>);
+/// Parses a printed modifier like `(+2)` or `(−1)` into its signed value.
+fn parse_modifier(text: &str) -> anyhow::Result<i32> {
+	let text = text
+		.trim()
+		.trim_start_matches('(')
+		.trim_end_matches(')')
+		.trim()
+		.replace('\u{2212}', "-");
+	Ok(text.parse::<i32>()?)
+}
+
+struct DescriptionBlockHtml<'doc>(Scope<'doc>);
+impl<'doc> DescriptionBlockHtml<'doc> {
+	pub fn heading(&self) -> Option<String> {
+		let heading = self
+			.0
+			.select_optional(&selectors().description_block_heading);
+		heading.map(|heading| collapse_text(&heading.element()))
+	}
+
+	/// Returns each paragraph of the block's content, along with the name of the entry it starts (if any).
+	pub fn paragraphs(&self) -> Vec<(Option<String>, RichText)> {
+		let content = self
+			.0
+			.select_optional(&selectors().description_block_content);
+		let Some(content) = content else {
+			return Vec::new();
+		};
+		content
+			.element()
+			.children()
+			.filter_map(scraper::ElementRef::wrap)
+			.map(|paragraph| Self::split_entry_name(&paragraph))
+			.filter(|(_, text)| !text.is_empty())
+			.collect()
+	}
 
-struct Tidbits<'doc>(scraper::ElementRef<'doc>);
+	/// Entries start with their name in bold (and sometimes italics), like `<em><strong>Bite.</strong></em>`.
+	/// Splits that name off of the paragraph, leaving the rest of the text and markup.
+	fn split_entry_name(paragraph: &scraper::ElementRef<'doc>) -> (Option<String>, RichText) {
+		let html = paragraph.inner_html().trim().to_owned();
+		let text = collapse_text(paragraph);
+		let first_node = paragraph
+			.children()
+			.find(|node| match node.value().as_text() {
+				Some(text) => !text.trim().is_empty(),
+				None => true,
+			});
+		let name_element = first_node
+			.and_then(scraper::ElementRef::wrap)
+			.filter(|element| matches!(element.value().name(), "strong" | "em" | "b" | "i"));
+		if let Some(element) = name_element {
+			let name_text = collapse_text(&element);
+			if let Some(name) = name_text.strip_suffix('.') {
+				let html = html.replacen(&element.html(), "", 1).trim().to_owned();
+				let text = text
+					.strip_prefix(&name_text)
+					.unwrap_or(&text)
+					.trim()
+					.to_owned();
+				return (Some(name.trim().to_owned()), RichText::new(html, text));
+			}
+		}
+		(None, RichText::new(html, text))
+	}
+}
+
+/// The rows below the ability scores (Saving Throws, Skills, Senses, etc),
+/// stored as the label of each row and its text content.
+struct Tidbits(Vec<(String, String)>);
+impl Tidbits {
+	fn from(html: Scope<'_>) -> anyhow::Result<Self> {
+		let mut entries = Vec::new();
+		for element in html.select_all(&selectors().tidbit) {
+			let label = element
+				.select(&selectors().tidbit_label, "tidbit label")?
+				.element();
+			let data = element
+				.select(&selectors().tidbit_data, "tidbit data")
+				.with_context(|| format!("parse tidbit data for {:?}", collapse_text(&label)))?
+				.element();
+			entries.push((collapse_text(&label), collapse_text(&data)));
+		}
+		Ok(Self(entries))
+	}
+
+	fn get(&self, label: &str) -> Option<&String> {
+		self.0
+			.iter()
+			.find(|(key, _)| key == label)
+			.map(|(_, value)| value)
+	}
+
+	pub fn saving_throws(&self) -> anyhow::Result<Vec<SavingThrow>> {
+		self.get("Saving Throws")
+			.map(|text| tidbit::parse_list(text))
+			.unwrap_or_default()
+			.iter()
+			.map(|entry| entry.parse::<SavingThrow>())
+			.collect()
+	}
+
+	pub fn skills(&self) -> anyhow::Result<Vec<Skill>> {
+		self.get("Skills")
+			.map(|text| tidbit::parse_list(text))
+			.unwrap_or_default()
+			.iter()
+			.map(|entry| entry.parse::<Skill>())
+			.collect()
+	}
+
+	pub fn damage_vulnerabilities(&self) -> Vec<String> {
+		self.get("Damage Vulnerabilities")
+			.map(|text| tidbit::parse_damage_types(text))
+			.unwrap_or_default()
+	}
+
+	pub fn damage_resistances(&self) -> Vec<String> {
+		self.get("Damage Resistances")
+			.map(|text| tidbit::parse_damage_types(text))
+			.unwrap_or_default()
+	}
+
+	pub fn damage_immunities(&self) -> Vec<String> {
+		self.get("Damage Immunities")
+			.map(|text| tidbit::parse_damage_types(text))
+			.unwrap_or_default()
+	}
+
+	pub fn condition_immunities(&self) -> Vec<String> {
+		self.get("Condition Immunities")
+			.map(|text| tidbit::parse_list(text))
+			.unwrap_or_default()
+	}
+
+	pub fn senses(&self) -> anyhow::Result<Senses> {
+		match self.get("Senses") {
+			Some(text) => text.parse::<Senses>(),
+			None => Ok(Senses::default()),
+		}
+	}
+
+	pub fn languages(&self) -> Vec<String> {
+		self.get("Languages")
+			.map(|text| tidbit::parse_list(text))
+			.unwrap_or_default()
+	}
+
+	/// The creature's challenge, which some creatures (like summoned spirits) show as a dash.
+	pub fn challenge(&self) -> anyhow::Result<Option<Challenge>> {
+		self.get("Challenge")
+			.filter(|text| !tidbit::is_dash(text))
+			.map(|text| text.parse::<Challenge>())
+			.transpose()
+	}
+
+	pub fn proficiency_bonus(&self) -> anyhow::Result<Option<i32>> {
+		self.get("Proficiency Bonus")
+			.map(|text| parse_modifier(text))
+			.transpose()
+	}
+}
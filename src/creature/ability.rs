@@ -0,0 +1,157 @@
+use std::str::FromStr;
+
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Ability {
+	Strength,
+	Dexterity,
+	Constitution,
+	Intelligence,
+	Wisdom,
+	Charisma,
+}
+impl Ability {
+	pub fn all() -> [Self; 6] {
+		[
+			Self::Strength,
+			Self::Dexterity,
+			Self::Constitution,
+			Self::Intelligence,
+			Self::Wisdom,
+			Self::Charisma,
+		]
+	}
+
+	pub fn abbreviation(&self) -> &'static str {
+		match self {
+			Self::Strength => "STR",
+			Self::Dexterity => "DEX",
+			Self::Constitution => "CON",
+			Self::Intelligence => "INT",
+			Self::Wisdom => "WIS",
+			Self::Charisma => "CHA",
+		}
+	}
+}
+impl std::fmt::Display for Ability {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.abbreviation())
+	}
+}
+impl FromStr for Ability {
+	type Err = InvalidAbility;
+
+	/// Parses either the abbreviation (`STR`, `Dex`) or the full name (`Strength`) of an ability.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let lowercase = s.trim().to_lowercase();
+		for ability in Self::all() {
+			let full_name = format!("{ability:?}").to_lowercase();
+			if lowercase == ability.abbreviation().to_lowercase() || lowercase == full_name {
+				return Ok(ability);
+			}
+		}
+		Err(InvalidAbility(s.to_owned()))
+	}
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub struct InvalidAbility(String);
+impl std::fmt::Display for InvalidAbility {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Invalid ability name {:?}", self.0)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct AbilityScore {
+	score: u32,
+	modifier: i32,
+}
+impl AbilityScore {
+	/// Creates a score with its printed modifier, which is kept even if it is not the one the score implies
+	/// (see [`AbilityScore::has_expected_modifier`]).
+	pub fn new(score: u32, modifier: i32) -> Self {
+		Self { score, modifier }
+	}
+
+	pub fn modifier_for(score: u32) -> i32 {
+		(score as i32 - 10).div_euclid(2)
+	}
+
+	pub fn score(&self) -> u32 {
+		self.score
+	}
+
+	pub fn modifier(&self) -> i32 {
+		self.modifier
+	}
+
+	/// True if the printed modifier is the one the score implies.
+	pub fn has_expected_modifier(&self) -> bool {
+		self.modifier == Self::modifier_for(self.score)
+	}
+}
+impl std::fmt::Display for AbilityScore {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} ({:+})", self.score, self.modifier)
+	}
+}
+
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+pub struct AbilityScores {
+	strength: AbilityScore,
+	dexterity: AbilityScore,
+	constitution: AbilityScore,
+	intelligence: AbilityScore,
+	wisdom: AbilityScore,
+	charisma: AbilityScore,
+}
+impl AbilityScores {
+	pub fn get(&self, ability: Ability) -> &AbilityScore {
+		match ability {
+			Ability::Strength => &self.strength,
+			Ability::Dexterity => &self.dexterity,
+			Ability::Constitution => &self.constitution,
+			Ability::Intelligence => &self.intelligence,
+			Ability::Wisdom => &self.wisdom,
+			Ability::Charisma => &self.charisma,
+		}
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (Ability, &AbilityScore)> + '_ {
+		Ability::all()
+			.into_iter()
+			.map(move |ability| (ability, self.get(ability)))
+	}
+}
+impl TryFrom<Vec<(Ability, AbilityScore)>> for AbilityScores {
+	type Error = MissingAbility;
+
+	fn try_from(scores: Vec<(Ability, AbilityScore)>) -> Result<Self, Self::Error> {
+		let find = |ability: Ability| {
+			scores
+				.iter()
+				.find(|(key, _)| *key == ability)
+				.map(|(_, score)| *score)
+				.ok_or(MissingAbility(ability))
+		};
+		Ok(Self {
+			strength: find(Ability::Strength)?,
+			dexterity: find(Ability::Dexterity)?,
+			constitution: find(Ability::Constitution)?,
+			intelligence: find(Ability::Intelligence)?,
+			wisdom: find(Ability::Wisdom)?,
+			charisma: find(Ability::Charisma)?,
+		})
+	}
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub struct MissingAbility(Ability);
+impl std::fmt::Display for MissingAbility {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Stat block is missing the {} score", self.0)
+	}
+}
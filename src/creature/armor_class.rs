@@ -0,0 +1,217 @@
+use super::split_outside_parentheses;
+use anyhow::Context;
+use std::str::FromStr;
+
+/// The armor which can be worn, as named in the rules.
+static ARMOR: [&str; 13] = [
+	"padded",
+	"leather",
+	"studded leather",
+	"hide",
+	"chain shirt",
+	"scale mail",
+	"breastplate",
+	"half plate",
+	"ring mail",
+	"chain mail",
+	"splint",
+	"plate",
+	"armor scraps",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArmorSourceKind {
+	NaturalArmor,
+	/// Worn armor, like `chain shirt` or `+1 plate`.
+	Armor,
+	Shield,
+	/// Anything else, like `mage armor` or `Unarmored Defense`.
+	Other,
+}
+
+/// Something which contributes to a creature's armor class.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct ArmorSource {
+	kind: ArmorSourceKind,
+	/// The source as written in the stat block.
+	name: String,
+}
+impl ArmorSource {
+	pub fn kind(&self) -> ArmorSourceKind {
+		self.kind
+	}
+
+	pub fn name(&self) -> &String {
+		&self.name
+	}
+}
+impl FromStr for ArmorSource {
+	type Err = anyhow::Error;
+
+	/// Parses a single source, like `natural armor` or `Chain Shirt`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let name = s.trim();
+		let lowercase = name.to_lowercase();
+		let kind = match lowercase.as_str() {
+			"natural armor" => ArmorSourceKind::NaturalArmor,
+			"shield" => ArmorSourceKind::Shield,
+			_ if ARMOR.iter().any(|armor| lowercase.contains(armor)) => ArmorSourceKind::Armor,
+			_ => ArmorSourceKind::Other,
+		};
+		Ok(Self {
+			kind,
+			name: name.to_owned(),
+		})
+	}
+}
+impl std::fmt::Display for ArmorSource {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.name)
+	}
+}
+
+/// An armor class the creature only has sometimes, like `15 with mage armor`
+/// or `12 (natural armor) in wolf or hybrid form`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct ConditionalArmorClass {
+	value: u32,
+	sources: Vec<ArmorSource>,
+	condition: String,
+}
+impl ConditionalArmorClass {
+	pub fn value(&self) -> u32 {
+		self.value
+	}
+
+	pub fn sources(&self) -> &Vec<ArmorSource> {
+		&self.sources
+	}
+
+	pub fn condition(&self) -> &String {
+		&self.condition
+	}
+}
+impl FromStr for ConditionalArmorClass {
+	type Err = anyhow::Error;
+
+	/// Parses an alternate armor class, like `19 with shield` or `12 (Natural Armor) In Wolf or Hybrid Form`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let regex = regex::Regex::new(r"^(\d+)(?: \((.+?)\))? (.+)$")?;
+		let captures = regex
+			.captures(s.trim())
+			.with_context(|| format!("parse conditional armor class {s:?}"))?;
+		Ok(Self {
+			value: captures[1].parse::<u32>()?,
+			sources: match captures.get(2) {
+				Some(sources) => parse_sources(sources.as_str())?,
+				None => Vec::new(),
+			},
+			condition: captures[3].to_owned(),
+		})
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct ArmorClass {
+	value: u32,
+	/// What the armor class comes from, like natural armor or a shield.
+	sources: Vec<ArmorSource>,
+	/// When the armor class applies, if the creature has others under other conditions (e.g. `in humanoid form`).
+	condition: Option<String>,
+	/// The armor classes the creature has under other conditions.
+	alternates: Vec<ConditionalArmorClass>,
+	/// The whole text of the armor class, if any of it was not understood
+	/// (like `11 + the level of the spell (natural armor)`).
+	raw: Option<String>,
+}
+impl ArmorClass {
+	pub fn value(&self) -> u32 {
+		self.value
+	}
+
+	pub fn sources(&self) -> &Vec<ArmorSource> {
+		&self.sources
+	}
+
+	pub fn condition(&self) -> Option<&String> {
+		self.condition.as_ref()
+	}
+
+	pub fn alternates(&self) -> &Vec<ConditionalArmorClass> {
+		&self.alternates
+	}
+
+	pub fn raw(&self) -> Option<&String> {
+		self.raw.as_ref()
+	}
+}
+impl FromStr for ArmorClass {
+	type Err = anyhow::Error;
+
+	/// Parses the full armor class text, like `16 (Chain Shirt, Shield)`, `17 (natural armor), 19 with shield`,
+	/// `12 (15 with mage armor)`, or `11 (In Humanoid Form, 12 (Natural Armor) In Wolf or Hybrid Form)`.
+	///
+	/// Only the value is required, and the whole text is kept in `raw` if the rest is not understood.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let regex = regex::Regex::new(r"^(\d+)(.*)$")?;
+		let captures = regex
+			.captures(s.trim())
+			.with_context(|| format!("parse armor class {s:?}"))?;
+		let mut armor_class = Self {
+			value: captures[1].parse::<u32>()?,
+			..Default::default()
+		};
+		let mut understood = true;
+		for part in split_outside_parentheses(&captures[2], &[',', ';']) {
+			let notes = part
+				.strip_prefix('(')
+				.and_then(|notes| notes.strip_suffix(')'));
+			match notes {
+				// Within the parentheses are the sources, and any conditions or alternate armor classes.
+				Some(notes) => {
+					for note in split_outside_parentheses(notes, &[',', ';']) {
+						if starts_with_digit(note) {
+							match note.parse() {
+								Ok(alternate) => armor_class.alternates.push(alternate),
+								Err(_) => understood = false,
+							}
+						} else if is_condition(note) {
+							armor_class.condition = Some(note.to_owned());
+						} else {
+							armor_class.sources.push(note.parse()?);
+						}
+					}
+				}
+				None if starts_with_digit(part) => match part.parse() {
+					Ok(alternate) => armor_class.alternates.push(alternate),
+					Err(_) => understood = false,
+				},
+				None => understood = false,
+			}
+		}
+		if !understood {
+			armor_class.raw = Some(s.trim().to_owned());
+		}
+		Ok(armor_class)
+	}
+}
+/// Splits a comma separated list of sources, like `Chain Shirt, Shield`.
+fn parse_sources(text: &str) -> anyhow::Result<Vec<ArmorSource>> {
+	split_outside_parentheses(text, &[','])
+		.into_iter()
+		.map(ArmorSource::from_str)
+		.collect()
+}
+
+fn starts_with_digit(text: &str) -> bool {
+	text.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// True if the note says when the armor class applies (e.g. `in humanoid form`), rather than where it comes from.
+fn is_condition(note: &str) -> bool {
+	let lowercase = note.to_lowercase();
+	["in ", "while ", "when ", "with ", "if ", "without "]
+		.iter()
+		.any(|word| lowercase.starts_with(word))
+}
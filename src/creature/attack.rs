@@ -0,0 +1,342 @@
+use super::dice::DiceExpression;
+use anyhow::Context;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackKind {
+	Melee,
+	Ranged,
+	MeleeOrRanged,
+}
+impl FromStr for AttackKind {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"Melee" => Ok(Self::Melee),
+			"Ranged" => Ok(Self::Ranged),
+			"Melee or Ranged" => Ok(Self::MeleeOrRanged),
+			_ => Err(anyhow::anyhow!("Invalid attack kind {s:?}")),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackSource {
+	Weapon,
+	Spell,
+}
+impl FromStr for AttackSource {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"Weapon" => Ok(Self::Weapon),
+			"Spell" => Ok(Self::Spell),
+			_ => Err(anyhow::anyhow!("Invalid attack source {s:?}")),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageType {
+	Acid,
+	Bludgeoning,
+	Cold,
+	Fire,
+	Force,
+	Lightning,
+	Necrotic,
+	Piercing,
+	Poison,
+	Psychic,
+	Radiant,
+	Slashing,
+	Thunder,
+}
+impl DamageType {
+	pub fn all() -> [Self; 13] {
+		[
+			Self::Acid,
+			Self::Bludgeoning,
+			Self::Cold,
+			Self::Fire,
+			Self::Force,
+			Self::Lightning,
+			Self::Necrotic,
+			Self::Piercing,
+			Self::Poison,
+			Self::Psychic,
+			Self::Radiant,
+			Self::Slashing,
+			Self::Thunder,
+		]
+	}
+}
+impl std::fmt::Display for DamageType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", format!("{self:?}").to_lowercase())
+	}
+}
+impl FromStr for DamageType {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let lowercase = s.trim().to_lowercase();
+		Self::all()
+			.into_iter()
+			.find(|kind| kind.to_string() == lowercase)
+			.ok_or_else(|| anyhow::anyhow!("Invalid damage type {s:?}"))
+	}
+}
+
+/// The normal and long range of a ranged attack, in feet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct AttackRange {
+	normal: u32,
+	long: Option<u32>,
+}
+impl AttackRange {
+	pub fn normal(&self) -> u32 {
+		self.normal
+	}
+
+	pub fn long(&self) -> Option<u32> {
+		self.long
+	}
+}
+
+/// A single component of the damage dealt by an attack, like `7 (1d8 + 3) slashing damage`.
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+pub struct Damage {
+	average: u32,
+	roll: Option<DiceExpression>,
+	damage_type: DamageType,
+}
+impl Damage {
+	pub fn average(&self) -> u32 {
+		self.average
+	}
+
+	pub fn roll(&self) -> Option<&DiceExpression> {
+		self.roll.as_ref()
+	}
+
+	pub fn damage_type(&self) -> DamageType {
+		self.damage_type
+	}
+
+	/// Parses the run of damage at the start of some text, like `7 (1d8 + 3) piercing damage plus 7 (2d6) fire damage`,
+	/// returning the components and the rest of the text after them.
+	/// Returns `None` if a component has dice or a damage type which is not understood.
+	fn parse_leading(text: &str) -> anyhow::Result<Option<(Vec<Self>, &str)>> {
+		let regex = regex::Regex::new(r"^(\d+)(?: \(([^)]*)\))? ([A-Za-z]+) damage")?;
+		let mut components = Vec::new();
+		let mut rest = text.trim_start();
+		while let Some(captures) = regex.captures(rest) {
+			let Ok(damage_type) = captures[3].parse::<DamageType>() else {
+				return Ok(None);
+			};
+			let roll = match captures.get(2) {
+				Some(roll) => match roll.as_str().parse::<DiceExpression>() {
+					Ok(roll) => Some(roll),
+					Err(_) => return Ok(None),
+				},
+				None => None,
+			};
+			components.push(Self {
+				average: captures[1].parse::<u32>()?,
+				roll,
+				damage_type,
+			});
+			rest = &rest[captures[0].len()..];
+			// Further components are joined by `plus`, like `plus 7 (2d6) fire damage`.
+			match rest.strip_prefix(" plus ") {
+				Some(next) if regex.is_match(next) => rest = next,
+				_ => break,
+			}
+		}
+		Ok(Some((components, rest)))
+	}
+}
+
+/// Damage dealt instead of the primary damage under some condition,
+/// like `or 8 (1d10 + 3) slashing damage if used with two hands`.
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+pub struct ConditionalDamage {
+	damage: Vec<Damage>,
+	condition: String,
+}
+impl ConditionalDamage {
+	pub fn damage(&self) -> &Vec<Damage> {
+		&self.damage
+	}
+
+	pub fn condition(&self) -> &String {
+		&self.condition
+	}
+}
+
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+pub struct Attack {
+	kind: AttackKind,
+	source: AttackSource,
+	to_hit: i32,
+	reach: Option<u32>,
+	range: Option<AttackRange>,
+	targets: u32,
+	damage: Vec<Damage>,
+	alternate_damage: Vec<ConditionalDamage>,
+	/// The rest of the hit after its damage, like a saving throw against further damage or being grappled.
+	effect: Option<String>,
+}
+impl Attack {
+	pub fn kind(&self) -> AttackKind {
+		self.kind
+	}
+
+	pub fn source(&self) -> AttackSource {
+		self.source
+	}
+
+	pub fn to_hit(&self) -> i32 {
+		self.to_hit
+	}
+
+	pub fn reach(&self) -> Option<u32> {
+		self.reach
+	}
+
+	pub fn range(&self) -> Option<&AttackRange> {
+		self.range.as_ref()
+	}
+
+	pub fn targets(&self) -> u32 {
+		self.targets
+	}
+
+	pub fn damage(&self) -> &Vec<Damage> {
+		&self.damage
+	}
+
+	pub fn alternate_damage(&self) -> &Vec<ConditionalDamage> {
+		&self.alternate_damage
+	}
+
+	pub fn effect(&self) -> Option<&String> {
+		self.effect.as_ref()
+	}
+
+	/// Parses the plain text of an action into an attack.
+	/// Returns `None` if the action is not a melee or ranged attack, or if its damage is not understood.
+	pub fn parse(text: &str) -> anyhow::Result<Option<Self>> {
+		let header_regex = regex::Regex::new(
+			r"^(Melee or Ranged|Melee|Ranged) (Weapon|Spell) Attack: ([+\-\u{2212}]\s*\d+) to hit",
+		)?;
+		let Some(header) = header_regex.captures(text) else {
+			return Ok(None);
+		};
+		let kind = header[1].parse::<AttackKind>()?;
+		let source = header[2].parse::<AttackSource>()?;
+		let to_hit = super::parse_modifier(&header[3].replace(' ', ""))
+			.with_context(|| format!("parse attack bonus {:?}", &header[3]))?;
+
+		let (details, hit) = match text.split_once("Hit:") {
+			Some((details, hit)) => (details, Some(hit)),
+			None => (text, None),
+		};
+
+		let reach_regex = regex::Regex::new(r"reach (\d+) ft\.")?;
+		let reach = match reach_regex.captures(details) {
+			Some(captures) => Some(captures[1].parse::<u32>()?),
+			None => None,
+		};
+
+		let range_regex = regex::Regex::new(r"range (\d+)(?:/(\d+))? ft\.")?;
+		let range = match range_regex.captures(details) {
+			Some(captures) => Some(AttackRange {
+				normal: captures[1].parse::<u32>()?,
+				long: match captures.get(2) {
+					Some(long) => Some(long.as_str().parse::<u32>()?),
+					None => None,
+				},
+			}),
+			None => None,
+		};
+
+		let targets_regex = regex::Regex::new(r"(\w+) (?:target|creature)s?\b")?;
+		let targets = targets_regex
+			.captures(details)
+			.and_then(|captures| parse_count(&captures[1]))
+			.unwrap_or(1);
+
+		let mut damage = Vec::new();
+		let mut alternate_damage = Vec::new();
+		let mut effect = None;
+		if let Some(hit) = hit {
+			// The hit starts with the damage dealt, which can be followed by damage dealt instead under some condition,
+			// and then anything else that happens to the target (saving throws, conditions, etc).
+			let Some((components, mut rest)) = Damage::parse_leading(hit)? else {
+				return Ok(None);
+			};
+			damage = components;
+			while let Some(clause) = rest.strip_prefix(", or ") {
+				let Some((components, after)) = Damage::parse_leading(clause)? else {
+					return Ok(None);
+				};
+				if components.is_empty() {
+					break;
+				}
+				let (condition, after) = split_condition(after);
+				alternate_damage.push(ConditionalDamage {
+					damage: components,
+					condition: condition.to_owned(),
+				});
+				rest = after;
+			}
+			let rest =
+				rest.trim_start_matches(|c: char| matches!(c, '.' | ',') || c.is_whitespace());
+			if !rest.is_empty() {
+				effect = Some(rest.to_owned());
+			}
+		}
+
+		Ok(Some(Self {
+			kind,
+			source,
+			to_hit,
+			reach,
+			range,
+			targets,
+			damage,
+			alternate_damage,
+			effect,
+		}))
+	}
+}
+
+/// Splits the condition of an alternate damage, like `if used with two hands`, from the rest of the hit.
+/// The condition ends at the end of its sentence or at the next alternate.
+fn split_condition(text: &str) -> (&str, &str) {
+	let end = [". ", ", or "]
+		.iter()
+		.filter_map(|separator| text.find(separator))
+		.min()
+		.unwrap_or(text.len());
+	let (condition, rest) = text.split_at(end);
+	(condition.trim().trim_end_matches('.'), rest)
+}
+
+fn parse_count(word: &str) -> Option<u32> {
+	static WORDS: [&str; 10] = [
+		"one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+	];
+	let lowercase = word.to_lowercase();
+	match WORDS.iter().position(|item| *item == lowercase) {
+		Some(idx) => Some(idx as u32 + 1),
+		None => word.parse::<u32>().ok(),
+	}
+}
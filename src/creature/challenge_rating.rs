@@ -0,0 +1,141 @@
+use std::str::FromStr;
+
+/// The challenge rating of a creature.
+/// Ratings below 1 are fractional (1/8, 1/4, 1/2), and all others are whole numbers up to 30.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ChallengeRating {
+	Zero,
+	Eighth,
+	Quarter,
+	Half,
+	Whole(WholeRating),
+}
+impl ChallengeRating {
+	pub const MAX: u8 = 30;
+
+	/// Creates a whole number rating, returning `None` if it is outside of `0..=30`.
+	pub fn whole(value: u8) -> Option<Self> {
+		match value {
+			0 => Some(Self::Zero),
+			1..=Self::MAX => Some(Self::Whole(WholeRating(value))),
+			_ => None,
+		}
+	}
+
+	pub fn as_f32(&self) -> f32 {
+		match self {
+			Self::Zero => 0.0,
+			Self::Eighth => 0.125,
+			Self::Quarter => 0.25,
+			Self::Half => 0.5,
+			Self::Whole(value) => value.value() as f32,
+		}
+	}
+
+	/// The experience points awarded for defeating a creature of this rating.
+	pub fn experience(&self) -> u32 {
+		static WHOLE_XP: [u32; 30] = [
+			200, 450, 700, 1100, 1800, 2300, 2900, 3900, 5000, 5900, 7200, 8400, 10000, 11500,
+			13000, 15000, 18000, 20000, 22000, 25000, 33000, 41000, 50000, 62000, 75000, 90000,
+			105000, 120000, 135000, 155000,
+		];
+		match self {
+			Self::Zero => 10,
+			Self::Eighth => 25,
+			Self::Quarter => 50,
+			Self::Half => 100,
+			Self::Whole(value) => WHOLE_XP[value.value() as usize - 1],
+		}
+	}
+
+	/// The proficiency bonus of a creature with this rating.
+	pub fn proficiency_bonus(&self) -> i32 {
+		match self {
+			Self::Whole(value) => 2 + (value.value() as i32 - 1) / 4,
+			_ => 2,
+		}
+	}
+}
+
+/// A whole number rating from 1 to [`ChallengeRating::MAX`], only created by [`ChallengeRating::whole`]
+/// so that every rating has a single value which orders the same as the rating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WholeRating(u8);
+impl WholeRating {
+	pub fn value(&self) -> u8 {
+		self.0
+	}
+}
+
+impl std::fmt::Display for ChallengeRating {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Zero => write!(f, "0"),
+			Self::Eighth => write!(f, "1/8"),
+			Self::Quarter => write!(f, "1/4"),
+			Self::Half => write!(f, "1/2"),
+			Self::Whole(value) => write!(f, "{}", value.value()),
+		}
+	}
+}
+impl FromStr for ChallengeRating {
+	type Err = InvalidChallengeRating;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim() {
+			"1/8" | "\u{215B}" => Ok(Self::Eighth),
+			"1/4" | "\u{00BC}" => Ok(Self::Quarter),
+			"1/2" | "\u{00BD}" => Ok(Self::Half),
+			text => text
+				.parse::<u8>()
+				.ok()
+				.and_then(Self::whole)
+				.ok_or_else(|| InvalidChallengeRating(s.to_owned())),
+		}
+	}
+}
+
+/// Ratings are serialized as they are printed (`"1/8"`, `"5"`).
+impl serde::Serialize for ChallengeRating {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		serializer.collect_str(self)
+	}
+}
+impl<'de> serde::Deserialize<'de> for ChallengeRating {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		let text = String::deserialize(deserializer)?;
+		text.parse::<Self>().map_err(serde::de::Error::custom)
+	}
+}
+impl schemars::JsonSchema for ChallengeRating {
+	fn schema_name() -> String {
+		"ChallengeRating".to_owned()
+	}
+
+	fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+		use schemars::schema::{InstanceType, SchemaObject};
+		let ratings = [Self::Zero, Self::Eighth, Self::Quarter, Self::Half]
+			.into_iter()
+			.chain((1..=Self::MAX).filter_map(Self::whole));
+		SchemaObject {
+			instance_type: Some(InstanceType::String.into()),
+			enum_values: Some(ratings.map(|cr| cr.to_string().into()).collect()),
+			..Default::default()
+		}
+		.into()
+	}
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub struct InvalidChallengeRating(String);
+impl std::fmt::Display for InvalidChallengeRating {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Invalid challenge rating {:?}", self.0)
+	}
+}
@@ -0,0 +1,157 @@
+use super::{attack::Attack, recharge::Recharge};
+use anyhow::Context;
+
+/// Text from the stat block which keeps its original markup (links to spells, conditions, etc)
+/// alongside a plain-text rendering.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct RichText {
+	html: String,
+	text: String,
+}
+impl RichText {
+	pub fn new(html: String, text: String) -> Self {
+		Self { html, text }
+	}
+
+	pub fn html(&self) -> &String {
+		&self.html
+	}
+
+	pub fn text(&self) -> &String {
+		&self.text
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.text.is_empty()
+	}
+
+	/// Appends another paragraph to this text.
+	pub fn push(&mut self, other: RichText) {
+		if self.is_empty() {
+			*self = other;
+			return;
+		}
+		self.html.push('\n');
+		self.html.push_str(&other.html);
+		self.text.push('\n');
+		self.text.push_str(&other.text);
+	}
+}
+
+/// A named feature of a creature, like the `Multiattack` action or the `Amphibious` trait.
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+pub struct Entry {
+	name: String,
+	description: RichText,
+	attack: Option<Attack>,
+	recharge: Option<Recharge>,
+}
+impl Entry {
+	pub fn new(name: String, description: RichText) -> Self {
+		Self {
+			name,
+			description,
+			attack: None,
+			recharge: None,
+		}
+	}
+
+	pub fn name(&self) -> &String {
+		&self.name
+	}
+
+	pub fn description(&self) -> &RichText {
+		&self.description
+	}
+
+	pub fn description_mut(&mut self) -> &mut RichText {
+		&mut self.description
+	}
+
+	pub fn attack(&self) -> Option<&Attack> {
+		self.attack.as_ref()
+	}
+
+	pub fn recharge(&self) -> Option<&Recharge> {
+		self.recharge.as_ref()
+	}
+}
+
+/// One of the sections of the stat block (Actions, Reactions, Legendary Actions, etc).
+#[derive(Debug, Clone, Default, serde::Serialize, schemars::JsonSchema)]
+pub struct DescriptionBlock {
+	/// Text before the first named entry, like the rules for how many legendary actions can be taken.
+	intro: RichText,
+	entries: Vec<Entry>,
+}
+impl DescriptionBlock {
+	pub fn intro(&self) -> &RichText {
+		&self.intro
+	}
+
+	pub fn entries(&self) -> &Vec<Entry> {
+		&self.entries
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.intro.is_empty() && self.entries.is_empty()
+	}
+
+	/// Adds a paragraph to the block.
+	/// Paragraphs with a name start a new entry, unnamed paragraphs continue the previous entry
+	/// (or the intro, if there are no entries yet).
+	pub fn push(&mut self, name: Option<String>, text: RichText) {
+		match (name, self.entries.last_mut()) {
+			(Some(name), _) => self.entries.push(Entry::new(name, text)),
+			(None, Some(entry)) => entry.description_mut().push(text),
+			(None, None) => self.intro.push(text),
+		}
+	}
+
+	/// Parses the text of each entry which describes a melee or ranged attack,
+	/// and the name of each entry which recharges on a roll.
+	pub fn parse_entries(&mut self) -> anyhow::Result<()> {
+		for entry in self.entries.iter_mut() {
+			entry.attack = Attack::parse(entry.description.text())
+				.with_context(|| format!("parse attack {:?}", entry.name))?;
+			entry.recharge = Recharge::find(&entry.name)?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+	Traits,
+	Actions,
+	BonusActions,
+	Reactions,
+	LegendaryActions,
+	MythicActions,
+}
+impl BlockKind {
+	/// Determines the kind of block from its heading. Blocks without a heading are the creature's traits.
+	pub fn from_heading(heading: Option<&str>) -> Option<Self> {
+		match heading.map(str::trim) {
+			None | Some("") => Some(Self::Traits),
+			Some("Actions") => Some(Self::Actions),
+			Some("Bonus Actions") => Some(Self::BonusActions),
+			Some("Reactions") => Some(Self::Reactions),
+			Some("Legendary Actions") => Some(Self::LegendaryActions),
+			Some("Mythic Actions") => Some(Self::MythicActions),
+			Some(_) => None,
+		}
+	}
+}
+impl std::fmt::Display for BlockKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Traits => write!(f, "Traits"),
+			Self::Actions => write!(f, "Actions"),
+			Self::BonusActions => write!(f, "Bonus Actions"),
+			Self::Reactions => write!(f, "Reactions"),
+			Self::LegendaryActions => write!(f, "Legendary Actions"),
+			Self::MythicActions => write!(f, "Mythic Actions"),
+		}
+	}
+}
@@ -0,0 +1,127 @@
+/// How [`Creature::parse`](super::Creature::parse) handles fields of the stat block it cannot parse.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+	/// Any field which cannot be parsed fails the whole creature.
+	#[default]
+	Strict,
+	/// Fields which cannot be parsed are left empty and recorded as [`Diagnostic`]s on the creature.
+	/// Only the page structure and the creature's name and url are still required.
+	Lenient,
+}
+
+/// A field of the stat block which could not be parsed when parsing leniently.
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+pub struct Diagnostic {
+	/// The field of the creature which was left empty (e.g. `"armor class"`).
+	field: String,
+	/// The error, followed by each of the errors which caused it.
+	chain: Vec<String>,
+}
+impl Diagnostic {
+	pub fn new(field: &str, error: &anyhow::Error) -> Self {
+		Self {
+			field: field.to_owned(),
+			chain: error.chain().map(|cause| cause.to_string()).collect(),
+		}
+	}
+
+	pub fn field(&self) -> &String {
+		&self.field
+	}
+
+	pub fn chain(&self) -> &Vec<String> {
+		&self.chain
+	}
+}
+impl std::fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: {}", self.field, self.chain.join(": "))
+	}
+}
+
+/// A field of the stat block which could not be parsed when parsing strictly,
+/// wrapping the error so the field can be found in the error's chain (see [`crate::report::Failure`]).
+#[derive(thiserror::Error, Debug)]
+pub struct FieldError {
+	field: String,
+	#[source]
+	source: anyhow::Error,
+}
+impl FieldError {
+	pub fn field(&self) -> &String {
+		&self.field
+	}
+}
+impl std::fmt::Display for FieldError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Failed to parse {}", self.field)
+	}
+}
+
+/// Parses each field of a creature independently, either failing on the first error (strict)
+/// or collecting the errors as diagnostics and leaving the field empty (lenient).
+pub(super) struct FieldParser {
+	mode: ParseMode,
+	diagnostics: Vec<Diagnostic>,
+}
+impl FieldParser {
+	pub fn new(mode: ParseMode) -> Self {
+		Self {
+			mode,
+			diagnostics: Vec::new(),
+		}
+	}
+
+	/// Returns the value of the field, or `None` if it failed to parse leniently.
+	pub fn parse<T>(
+		&mut self,
+		field: &str,
+		result: anyhow::Result<T>,
+	) -> anyhow::Result<Option<T>> {
+		match (result, self.mode) {
+			(Ok(value), _) => Ok(Some(value)),
+			(Err(err), ParseMode::Strict) => Err(FieldError {
+				field: field.to_owned(),
+				source: err,
+			}
+			.into()),
+			(Err(err), ParseMode::Lenient) => {
+				self.diagnostics.push(Diagnostic::new(field, &err));
+				Ok(None)
+			}
+		}
+	}
+
+	/// Returns the value of the field, or its default (empty) value if it failed to parse leniently.
+	pub fn parse_or_default<T>(
+		&mut self,
+		field: &str,
+		result: anyhow::Result<T>,
+	) -> anyhow::Result<T>
+	where
+		T: Default,
+	{
+		Ok(self.parse(field, result)?.unwrap_or_default())
+	}
+
+	/// Parses a field from a section of the stat block, which may itself have failed to parse.
+	/// The field is left empty without another diagnostic if the section is missing.
+	pub fn parse_from<S, T>(
+		&mut self,
+		section: Option<&S>,
+		field: &str,
+		parse: impl FnOnce(&S) -> anyhow::Result<T>,
+	) -> anyhow::Result<T>
+	where
+		T: Default,
+	{
+		match section {
+			Some(section) => self.parse_or_default(field, parse(section)),
+			None => Ok(T::default()),
+		}
+	}
+
+	pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+		self.diagnostics
+	}
+}
@@ -0,0 +1,271 @@
+use anyhow::Context;
+use rand::Rng;
+use std::str::FromStr;
+
+/// A single term of a [`DiceExpression`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DiceTerm {
+	/// Some number of dice with the same number of sides, like `2d6`.
+	/// A negative count is subtracted from the total (e.g. the `- 1d4` of `2d6 - 1d4`).
+	Dice {
+		count: i32,
+		sides: u32,
+	},
+	Constant {
+		value: i32,
+	},
+}
+impl DiceTerm {
+	pub fn minimum(&self) -> i32 {
+		match *self {
+			Self::Dice { count, sides } if count < 0 => {
+				count.saturating_mul(Self::sides_i32(sides))
+			}
+			Self::Dice { count, .. } => count,
+			Self::Constant { value } => value,
+		}
+	}
+
+	pub fn maximum(&self) -> i32 {
+		match *self {
+			Self::Dice { count, .. } if count < 0 => count,
+			Self::Dice { count, sides } => count.saturating_mul(Self::sides_i32(sides)),
+			Self::Constant { value } => value,
+		}
+	}
+
+	fn sides_i32(sides: u32) -> i32 {
+		i32::try_from(sides).unwrap_or(i32::MAX)
+	}
+
+	pub fn average(&self) -> f64 {
+		match *self {
+			Self::Dice { count, sides } => count as f64 * (sides as f64 + 1.0) / 2.0,
+			Self::Constant { value } => value as f64,
+		}
+	}
+
+	pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
+		match *self {
+			Self::Dice { count, sides } => {
+				let total = (0..count.unsigned_abs())
+					.map(|_| Self::sides_i32(rng.gen_range(1..=sides)))
+					.fold(0, i32::saturating_add);
+				total.saturating_mul(count.signum())
+			}
+			Self::Constant { value } => value,
+		}
+	}
+}
+
+/// A sum of dice and constants, like `2d8 + 2` or `2d6 + 1d8 + 4`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DiceExpression {
+	terms: Vec<DiceTerm>,
+}
+/// The serialized form of a [`DiceExpression`].
+#[derive(serde::Serialize, schemars::JsonSchema)]
+struct DiceExpressionFields<'a> {
+	terms: &'a Vec<DiceTerm>,
+	minimum: i32,
+	maximum: i32,
+	average: f64,
+}
+impl DiceExpression {
+	/// The most dice a single term can have, far more than any stat block rolls (e.g. the `33d20` of the tarrasque).
+	pub const MAX_COUNT: u32 = 100;
+	/// The most sides a die can have (a `d100`).
+	pub const MAX_SIDES: u32 = 100;
+	/// The most totals [`DiceExpression::chance_at_least`] will work out the chance of rolling,
+	/// so that it stays quick for any dice.
+	const MAX_DISTRIBUTION_LEN: u64 = 1000;
+
+	pub fn terms(&self) -> &Vec<DiceTerm> {
+		&self.terms
+	}
+
+	/// The smallest total which can be rolled.
+	pub fn minimum(&self) -> i32 {
+		self.terms
+			.iter()
+			.map(DiceTerm::minimum)
+			.fold(0, i32::saturating_add)
+	}
+
+	/// The largest total which can be rolled.
+	pub fn maximum(&self) -> i32 {
+		self.terms
+			.iter()
+			.map(DiceTerm::maximum)
+			.fold(0, i32::saturating_add)
+	}
+
+	/// The mean of all totals which can be rolled, which is always a whole or half number.
+	pub fn average(&self) -> f64 {
+		self.terms.iter().map(DiceTerm::average).sum()
+	}
+
+	/// The chance of rolling at least the target, from 0 to 1.
+	/// Returns `None` if the dice can roll too many different totals to work it out exactly.
+	pub fn chance_at_least(&self, target: i32) -> Option<f64> {
+		let minimum = self.minimum() as i64;
+		let chance = self
+			.distribution()?
+			.into_iter()
+			.enumerate()
+			.filter(|(offset, _)| minimum + *offset as i64 >= target as i64)
+			.map(|(_, chance)| chance)
+			.sum();
+		Some(chance)
+	}
+
+	/// The chance of rolling each total, from the minimum (at index 0) to the maximum,
+	/// or `None` if there are more than [`DiceExpression::MAX_DISTRIBUTION_LEN`] totals.
+	///
+	/// Each die adds 0 to `sides - 1` above its own minimum with equal chance (whether it is added or subtracted),
+	/// so the distribution is built up by adding the dice one at a time.
+	fn distribution(&self) -> Option<Vec<f64>> {
+		let len = self.terms.iter().fold(1u64, |len, term| match *term {
+			DiceTerm::Dice { count, sides } => {
+				let spread = (count.unsigned_abs() as u64)
+					.saturating_mul(sides as u64)
+					.saturating_sub(1);
+				len.saturating_add(spread)
+			}
+			DiceTerm::Constant { .. } => len,
+		});
+		if len > Self::MAX_DISTRIBUTION_LEN {
+			return None;
+		}
+		let mut chances = vec![1.0];
+		for term in self.terms.iter() {
+			let DiceTerm::Dice { count, sides } = *term else {
+				continue;
+			};
+			let sides = sides as usize;
+			for _ in 0..count.unsigned_abs() {
+				let mut next = vec![0.0; chances.len() + sides - 1];
+				for (offset, chance) in chances.iter().enumerate() {
+					for face in 0..sides {
+						next[offset + face] += chance / sides as f64;
+					}
+				}
+				chances = next;
+			}
+		}
+		Some(chances)
+	}
+
+	/// Rolls each of the dice and returns the total.
+	/// Pass a seeded rng (like [`rand::rngs::StdRng::seed_from_u64`](rand::SeedableRng::seed_from_u64))
+	/// to get the same rolls every time.
+	pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
+		self.terms
+			.iter()
+			.map(|term| term.roll(rng))
+			.fold(0, i32::saturating_add)
+	}
+}
+impl serde::Serialize for DiceExpression {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		let fields = DiceExpressionFields {
+			terms: &self.terms,
+			minimum: self.minimum(),
+			maximum: self.maximum(),
+			average: self.average(),
+		};
+		fields.serialize(serializer)
+	}
+}
+impl schemars::JsonSchema for DiceExpression {
+	fn schema_name() -> String {
+		"DiceExpression".to_owned()
+	}
+
+	fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+		DiceExpressionFields::json_schema(gen)
+	}
+}
+impl std::fmt::Debug for DiceExpression {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "DiceExpression({})", self)
+	}
+}
+impl std::fmt::Display for DiceExpression {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (idx, term) in self.terms.iter().enumerate() {
+			let (negative, text) = match *term {
+				DiceTerm::Dice { count, sides } => (count < 0, format!("{}d{sides}", count.abs())),
+				DiceTerm::Constant { value } => (value < 0, value.abs().to_string()),
+			};
+			match (idx, negative) {
+				(0, false) => write!(f, "{text}")?,
+				(0, true) => write!(f, "-{text}")?,
+				(_, false) => write!(f, " + {text}")?,
+				(_, true) => write!(f, " - {text}")?,
+			}
+		}
+		Ok(())
+	}
+}
+impl FromStr for DiceExpression {
+	type Err = anyhow::Error;
+
+	/// Parses a sum of dice and constants, like `2d6`, `1d8 + 3`, `3d10 - 1`, or `2d6 + 1d8 + 4`.
+	/// Each term has at most [`DiceExpression::MAX_COUNT`] dice of at most [`DiceExpression::MAX_SIDES`] sides.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let regex = regex::Regex::new(r"^\s*([+\-\u{2212}])?\s*(?:(\d*)d(\d+)|(\d+))\s*")?;
+		let mut terms = Vec::new();
+		let mut rest = s;
+		while !rest.trim().is_empty() {
+			let captures = regex
+				.captures(rest)
+				.with_context(|| format!("parse dice expression {s:?}: unexpected {rest:?}"))?;
+			let sign = match captures.get(1).map(|sign| sign.as_str()) {
+				None if !terms.is_empty() => {
+					anyhow::bail!("parse dice expression {s:?}: missing operator before {rest:?}")
+				}
+				None | Some("+") => 1,
+				Some(_) => -1,
+			};
+			let term = match captures.get(3) {
+				Some(sides) => {
+					let count = match captures.get(2).map(|count| count.as_str()) {
+						None | Some("") => 1,
+						Some(count) => count.parse::<u32>()?,
+					};
+					let sides = sides.as_str().parse::<u32>()?;
+					if sides == 0 {
+						anyhow::bail!(
+							"parse dice expression {s:?}: dice must have at least one side"
+						);
+					}
+					if count > Self::MAX_COUNT || sides > Self::MAX_SIDES {
+						anyhow::bail!(
+							"parse dice expression {s:?}: at most {}d{} can be rolled at once",
+							Self::MAX_COUNT,
+							Self::MAX_SIDES
+						);
+					}
+					DiceTerm::Dice {
+						count: count as i32 * sign,
+						sides,
+					}
+				}
+				None => DiceTerm::Constant {
+					value: captures[4].parse::<i32>()? * sign,
+				},
+			};
+			terms.push(term);
+			rest = &rest[captures[0].len()..];
+		}
+		if terms.is_empty() {
+			anyhow::bail!("parse dice expression {s:?}: no dice or constants");
+		}
+		Ok(Self { terms })
+	}
+}
@@ -0,0 +1,55 @@
+use super::dice::DiceExpression;
+use rand::Rng;
+
+/// How an ability is regained after it is used, like the `Recharge 5–6` of `Fire Breath (Recharge 5–6)`.
+/// At the start of each of the creature's turns the dice are rolled, and the ability is regained
+/// if the roll is at least the minimum.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct Recharge {
+	minimum: u32,
+	dice: DiceExpression,
+}
+impl Recharge {
+	pub fn minimum(&self) -> u32 {
+		self.minimum
+	}
+
+	pub fn dice(&self) -> &DiceExpression {
+		&self.dice
+	}
+
+	/// The chance of regaining the ability on each turn, from 0 to 1.
+	pub fn chance(&self) -> f64 {
+		// The dice are a single d6, which always has a distribution.
+		self.dice
+			.chance_at_least(self.minimum as i32)
+			.unwrap_or_default()
+	}
+
+	/// Rolls the dice, returning true if the ability is regained.
+	pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
+		self.dice.roll(rng) >= self.minimum as i32
+	}
+
+	/// Finds the recharge in the name of an entry, like `Fire Breath (Recharge 5–6)` or `Rotting Gaze (Recharge 6)`.
+	/// Returns `None` if the entry does not recharge on a roll (e.g. `Recharges after a Short or Long Rest`).
+	pub fn find(name: &str) -> anyhow::Result<Option<Self>> {
+		let regex = regex::Regex::new(r"\(Recharge (\d+)(?:\s*[-\u{2013}]\s*(\d+))?\)")?;
+		let Some(captures) = regex.captures(name) else {
+			return Ok(None);
+		};
+		let dice = "1d6".parse::<DiceExpression>()?;
+		let minimum = captures[1].parse::<u32>()?;
+		let maximum = match captures.get(2) {
+			Some(maximum) => maximum.as_str().parse::<i32>()?,
+			None => minimum as i32,
+		};
+		if maximum != dice.maximum() || minimum as i32 > maximum {
+			anyhow::bail!(
+				"parse recharge {name:?}: the range must end at {}",
+				dice.maximum()
+			);
+		}
+		Ok(Some(Self { minimum, dice }))
+	}
+}
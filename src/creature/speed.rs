@@ -0,0 +1,159 @@
+use super::{split_outside_parentheses, tidbit::is_dash};
+use anyhow::Context;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementMode {
+	Walk,
+	Burrow,
+	Climb,
+	Fly,
+	Swim,
+}
+impl MovementMode {
+	pub fn all() -> [Self; 5] {
+		[Self::Walk, Self::Burrow, Self::Climb, Self::Fly, Self::Swim]
+	}
+}
+impl std::fmt::Display for MovementMode {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", format!("{self:?}").to_lowercase())
+	}
+}
+impl FromStr for MovementMode {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let lowercase = s.trim().to_lowercase();
+		Self::all()
+			.into_iter()
+			.find(|mode| mode.to_string() == lowercase)
+			.ok_or_else(|| anyhow::anyhow!("Invalid movement mode {s:?}"))
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct Speed {
+	/// Missing only if the speed was not understood (see `raw`).
+	mode: Option<MovementMode>,
+	/// In feet. Missing only if the speed was not understood (see `raw`).
+	distance: Option<u32>,
+	/// The creature can hover (so it does not fall when it stops flying).
+	hover: bool,
+	/// When the speed applies, if only sometimes (e.g. `in bat form`).
+	condition: Option<String>,
+	/// The whole text of the speed, if any of it was not understood.
+	raw: Option<String>,
+}
+impl Speed {
+	pub fn mode(&self) -> Option<MovementMode> {
+		self.mode
+	}
+
+	pub fn distance(&self) -> Option<u32> {
+		self.distance
+	}
+
+	pub fn hover(&self) -> bool {
+		self.hover
+	}
+
+	pub fn condition(&self) -> Option<&String> {
+		self.condition.as_ref()
+	}
+
+	pub fn raw(&self) -> Option<&String> {
+		self.raw.as_ref()
+	}
+
+	/// Parses the full speed text, like `30 ft., fly 60 ft. (hover; in bat form)`.
+	///
+	/// A speed can give another distance for a form in its notes (`30 ft. (40 ft. in wolf form)`),
+	/// which is parsed as another speed of the same mode.
+	pub fn parse_all(text: &str) -> anyhow::Result<Vec<Self>> {
+		let mut speeds = Vec::new();
+		for entry in split_outside_parentheses(text, &[',', ';']) {
+			if is_dash(entry) {
+				continue;
+			}
+			speeds.extend(Self::parse_entry(entry)?);
+		}
+		Ok(speeds)
+	}
+
+	/// A speed which was not understood, keeping only its text.
+	fn unparsed(entry: &str) -> Self {
+		Self {
+			mode: None,
+			distance: None,
+			hover: false,
+			condition: None,
+			raw: Some(entry.to_owned()),
+		}
+	}
+
+	/// Parses a single speed, like `fly 30 ft. (hover)`, along with any speeds for other forms in its notes.
+	/// A speed without a distance or with an unknown mode (like `jump 10 ft.`) is kept as [`Speed::unparsed`].
+	fn parse_entry(entry: &str) -> anyhow::Result<Vec<Self>> {
+		let regex = regex::Regex::new(r"^(?:([A-Za-z]+) )?(\d+) ft\.?(.*)$")?;
+		let Some(captures) = regex.captures(entry) else {
+			return Ok(vec![Self::unparsed(entry)]);
+		};
+		let mode = match captures.get(1) {
+			Some(mode) => match mode.as_str().parse::<MovementMode>() {
+				Ok(mode) => mode,
+				Err(_) => return Ok(vec![Self::unparsed(entry)]),
+			},
+			None => MovementMode::Walk,
+		};
+		let mut speed = Self {
+			mode: Some(mode),
+			distance: Some(
+				captures[2]
+					.parse::<u32>()
+					.with_context(|| format!("parse speed {entry:?}"))?,
+			),
+			hover: false,
+			condition: None,
+			raw: None,
+		};
+
+		let notes = captures[3].trim();
+		if notes.is_empty() {
+			return Ok(vec![speed]);
+		}
+		let Some(notes) = notes
+			.strip_prefix('(')
+			.and_then(|notes| notes.strip_suffix(')'))
+		else {
+			speed.raw = Some(entry.to_owned());
+			return Ok(vec![speed]);
+		};
+
+		let form_regex = regex::Regex::new(r"^(\d+) ft\.? (.+)$")?;
+		let mut conditions = Vec::new();
+		let mut other_forms = Vec::new();
+		for note in split_outside_parentheses(notes, &[';']) {
+			if note.eq_ignore_ascii_case("hover") {
+				speed.hover = true;
+			} else if let Some(captures) = form_regex.captures(note) {
+				other_forms.push(Self {
+					mode: Some(mode),
+					distance: Some(captures[1].parse::<u32>()?),
+					hover: false,
+					condition: Some(captures[2].to_owned()),
+					raw: None,
+				});
+			} else {
+				conditions.push(note);
+			}
+		}
+		if !conditions.is_empty() {
+			speed.condition = Some(conditions.join("; "));
+		}
+		let mut speeds = vec![speed];
+		speeds.extend(other_forms);
+		Ok(speeds)
+	}
+}
@@ -0,0 +1,220 @@
+use super::{ability::Ability, challenge_rating::ChallengeRating, parse_modifier};
+use anyhow::Context;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct SavingThrow {
+	ability: Ability,
+	bonus: i32,
+}
+impl SavingThrow {
+	pub fn ability(&self) -> Ability {
+		self.ability
+	}
+
+	pub fn bonus(&self) -> i32 {
+		self.bonus
+	}
+}
+impl FromStr for SavingThrow {
+	type Err = anyhow::Error;
+
+	/// Parses a single saving throw entry, like `Dex +5`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (ability, bonus) = split_bonus(s)?;
+		Ok(Self {
+			ability: ability.parse::<Ability>()?,
+			bonus,
+		})
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct Skill {
+	name: String,
+	bonus: i32,
+}
+impl Skill {
+	pub fn name(&self) -> &String {
+		&self.name
+	}
+
+	pub fn bonus(&self) -> i32 {
+		self.bonus
+	}
+}
+impl FromStr for Skill {
+	type Err = anyhow::Error;
+
+	/// Parses a single skill entry, like `Perception +3`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (name, bonus) = split_bonus(s)?;
+		Ok(Self {
+			name: name.to_owned(),
+			bonus,
+		})
+	}
+}
+
+/// Splits `Name +X` into the name and signed bonus.
+fn split_bonus(text: &str) -> anyhow::Result<(&str, i32)> {
+	let (name, bonus) = text
+		.trim()
+		.rsplit_once(' ')
+		.with_context(|| format!("missing bonus in {text:?}"))?;
+	Ok((name.trim(), parse_modifier(bonus)?))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct Sense {
+	name: String,
+	/// In feet, if the sense has a range.
+	range: Option<u32>,
+	qualifier: Option<String>,
+}
+impl Sense {
+	pub fn name(&self) -> &String {
+		&self.name
+	}
+
+	pub fn range(&self) -> Option<u32> {
+		self.range
+	}
+
+	pub fn qualifier(&self) -> Option<&String> {
+		self.qualifier.as_ref()
+	}
+}
+impl FromStr for Sense {
+	type Err = anyhow::Error;
+
+	/// Parses a sense, like `Blindsight 30 ft. (blind beyond this radius)` or `Tremorsense`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let regex = regex::Regex::new(r"^(.+?)(?: (\d+) ft\.?)?(?: \((.+)\))?$")?;
+		let captures = regex
+			.captures(s.trim())
+			.with_context(|| format!("parse sense {s:?}"))?;
+		Ok(Self {
+			name: captures[1].to_owned(),
+			range: match captures.get(2) {
+				Some(range) => Some(range.as_str().parse::<u32>()?),
+				None => None,
+			},
+			qualifier: captures.get(3).map(|item| item.as_str().to_owned()),
+		})
+	}
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, schemars::JsonSchema)]
+pub struct Senses {
+	senses: Vec<Sense>,
+	passive_perception: Option<u32>,
+}
+impl Senses {
+	pub fn senses(&self) -> &Vec<Sense> {
+		&self.senses
+	}
+
+	pub fn passive_perception(&self) -> Option<u32> {
+		self.passive_perception
+	}
+}
+impl FromStr for Senses {
+	type Err = anyhow::Error;
+
+	/// Parses the full senses text, like `Darkvision 60 ft., Passive Perception 13`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut senses = Self::default();
+		for entry in s
+			.split(',')
+			.map(str::trim)
+			.filter(|entry| !entry.is_empty() && !is_dash(entry))
+		{
+			match entry.to_lowercase().strip_prefix("passive perception") {
+				Some(value) => {
+					senses.passive_perception = Some(
+						value
+							.trim()
+							.parse::<u32>()
+							.with_context(|| format!("parse passive perception {entry:?}"))?,
+					);
+				}
+				None => senses.senses.push(entry.parse::<Sense>()?),
+			}
+		}
+		Ok(senses)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct Challenge {
+	rating: ChallengeRating,
+	experience: u32,
+	/// The experience if the creature is used differently, like the `10` of `0 (0 or 10 XP)`
+	/// (which is only awarded if the creature has an attack).
+	alternate_experience: Option<u32>,
+}
+impl Challenge {
+	pub fn rating(&self) -> &ChallengeRating {
+		&self.rating
+	}
+
+	pub fn experience(&self) -> u32 {
+		self.experience
+	}
+
+	pub fn alternate_experience(&self) -> Option<u32> {
+		self.alternate_experience
+	}
+}
+impl FromStr for Challenge {
+	type Err = anyhow::Error;
+
+	/// Parses the challenge text, like `1/8 (25 XP)`, `5 (1,800 XP)`, or `0 (0 or 10 XP)`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let regex = regex::Regex::new(r"^([\d/]+)\s*\(([\d,]+)(?: or ([\d,]+))? XP")?;
+		let captures = regex
+			.captures(s.trim())
+			.with_context(|| format!("parse challenge {s:?}"))?;
+		Ok(Self {
+			rating: captures[1].parse::<ChallengeRating>()?,
+			experience: captures[2].replace(',', "").parse::<u32>()?,
+			alternate_experience: match captures.get(3) {
+				Some(experience) => Some(experience.as_str().replace(',', "").parse::<u32>()?),
+				None => None,
+			},
+		})
+	}
+}
+
+/// Splits a comma separated list, treating dashes as an empty list.
+pub fn parse_list(text: &str) -> Vec<String> {
+	text.split(',')
+		.map(|entry| entry.trim())
+		.map(|entry| entry.strip_prefix("and ").unwrap_or(entry))
+		.filter(|entry| !entry.is_empty() && !is_dash(entry))
+		.map(|entry| entry.to_owned())
+		.collect()
+}
+
+/// Splits a list of damage types.
+/// Groups are separated by semicolons, and a group with a qualifier
+/// (`bludgeoning, piercing, and slashing from nonmagical attacks`) is kept whole.
+pub fn parse_damage_types(text: &str) -> Vec<String> {
+	let mut types = Vec::new();
+	for group in text.split(';').map(str::trim) {
+		let is_qualified = [" from ", " that ", " except "]
+			.iter()
+			.any(|word| group.contains(word));
+		match is_qualified {
+			true => types.push(group.to_owned()),
+			false => types.extend(parse_list(group)),
+		}
+	}
+	types
+}
+
+/// True if the text is a dash, which the site shows for an empty value.
+pub fn is_dash(text: &str) -> bool {
+	matches!(text, "-" | "--" | "\u{2014}" | "\u{2013}")
+}
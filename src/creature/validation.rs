@@ -0,0 +1,166 @@
+use super::{
+	ability::{Ability, AbilityScore},
+	dice::{DiceExpression, DiceTerm},
+	Creature,
+};
+
+/// A value printed in the stat block which disagrees with the rest of the stat block, likely a typo on the page.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, schemars::JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DataWarning {
+	/// A printed average which disagrees with the dice it is printed next to, like `16 (2d8 + 6)`.
+	Average {
+		/// What the average is for (e.g. `"hit points"`, `"Bite damage in Actions"`, or `"Fire Breath in Actions"`
+		/// for an average in the text of an entry, like the damage of a saving throw).
+		field: String,
+		printed: u32,
+		dice: DiceExpression,
+		/// The average of the dice, rounded down the same as stat blocks.
+		computed: i32,
+	},
+	/// A printed ability modifier which disagrees with its score, like `14 (+1)`.
+	Modifier {
+		ability: Ability,
+		score: u32,
+		printed: i32,
+		expected: i32,
+	},
+}
+impl DataWarning {
+	/// Returns a warning if the printed average is not the average of the dice.
+	fn check_average(field: String, printed: u32, dice: &DiceExpression) -> Option<Self> {
+		let computed = dice.average().floor() as i32;
+		if printed as i32 == computed {
+			return None;
+		}
+		Some(Self::Average {
+			field,
+			printed,
+			dice: dice.clone(),
+			computed,
+		})
+	}
+
+	/// Returns a warning if the printed modifier is not the one the score implies.
+	fn check_modifier(ability: Ability, score: &AbilityScore) -> Option<Self> {
+		if score.has_expected_modifier() {
+			return None;
+		}
+		Some(Self::Modifier {
+			ability,
+			score: score.score(),
+			printed: score.modifier(),
+			expected: AbilityScore::modifier_for(score.score()),
+		})
+	}
+}
+impl std::fmt::Display for DataWarning {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Average {
+				field,
+				printed,
+				dice,
+				computed,
+			} => write!(
+				f,
+				"the printed average of {field} is {printed}, but {dice} averages {computed}"
+			),
+			Self::Modifier {
+				ability,
+				score,
+				printed,
+				expected,
+			} => write!(
+				f,
+				"the printed {ability} modifier is {printed:+}, but a score of {score} gives {expected:+}"
+			),
+		}
+	}
+}
+
+/// Checks the values printed in the creature's stat block which can be computed from the rest of it.
+pub fn check(creature: &Creature) -> anyhow::Result<Vec<DataWarning>> {
+	let mut warnings = check_modifiers(creature);
+	warnings.extend(check_averages(creature)?);
+	Ok(warnings)
+}
+
+/// Checks the printed modifier of each of the creature's ability scores.
+fn check_modifiers(creature: &Creature) -> Vec<DataWarning> {
+	let Some(scores) = creature.ability_scores() else {
+		return Vec::new();
+	};
+	scores
+		.iter()
+		.filter_map(|(ability, score)| DataWarning::check_modifier(ability, score))
+		.collect()
+}
+
+/// Checks the printed average of the creature's hit points, of the damage of each of its attacks,
+/// and of any other dice in the text of its entries (like the damage of a saving throw).
+fn check_averages(creature: &Creature) -> anyhow::Result<Vec<DataWarning>> {
+	let mut warnings = Vec::new();
+	if let Some((hit_points, Some(dice))) = creature.hit_points() {
+		warnings.extend(DataWarning::check_average(
+			"hit points".to_owned(),
+			*hit_points,
+			dice,
+		));
+	}
+	for (kind, block) in creature.description_blocks() {
+		for entry in block.entries() {
+			// The damage of an attack is checked on its own, leaving only the text after it (like a rider).
+			let text = match entry.attack() {
+				Some(attack) => attack.effect(),
+				None => Some(entry.description().text()),
+			};
+			if let Some(text) = text {
+				let field = format!("{} in {kind}", entry.name());
+				for (printed, dice) in find_averages(text)? {
+					warnings.extend(DataWarning::check_average(field.clone(), printed, &dice));
+				}
+			}
+
+			let Some(attack) = entry.attack() else {
+				continue;
+			};
+			let field = format!("{} damage in {kind}", entry.name());
+			let alternate_damage = attack
+				.alternate_damage()
+				.iter()
+				.flat_map(|alternate| alternate.damage());
+			for damage in attack.damage().iter().chain(alternate_damage) {
+				if let Some(dice) = damage.roll() {
+					warnings.extend(DataWarning::check_average(
+						field.clone(),
+						damage.average(),
+						dice,
+					));
+				}
+			}
+		}
+	}
+	Ok(warnings)
+}
+
+/// Finds each average printed along with its dice in the text, like the `21 (6d6)` of `taking 21 (6d6) fire damage`
+/// or the `10 (3d6)` of `regains 10 (3d6) hit points`.
+fn find_averages(text: &str) -> anyhow::Result<Vec<(u32, DiceExpression)>> {
+	let regex = regex::Regex::new(r"\b(\d+) \(([^()]+)\)")?;
+	let mut averages = Vec::new();
+	for captures in regex.captures_iter(text) {
+		// Other numbers are printed with notes in parentheses (e.g. `60 ft. (blind beyond this radius)`).
+		let Ok(dice) = captures[2].parse::<DiceExpression>() else {
+			continue;
+		};
+		let has_dice = dice
+			.terms()
+			.iter()
+			.any(|term| matches!(term, DiceTerm::Dice { .. }));
+		if has_dice {
+			averages.push((captures[1].parse::<u32>()?, dice));
+		}
+	}
+	Ok(averages)
+}
@@ -0,0 +1,269 @@
+use crate::{
+	creature::{ability::Ability, Creature},
+	dndbeyond::creature_list::CreatureListing,
+};
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+	QuerySelect, Set, TransactionTrait,
+};
+use sea_orm_migration::MigratorTrait;
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+};
+
+pub mod checkpoint;
+use checkpoint::Checkpoint;
+pub mod entity;
+mod migration;
+
+/// A local sqlite database of scraped creatures.
+#[derive(Clone)]
+pub struct Database(DatabaseConnection);
+impl Database {
+	/// Opens (or creates) the database file at the path, and applies any pending migrations.
+	pub async fn open(path: &Path) -> anyhow::Result<Self> {
+		if let Some(parent) = path.parent() {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+		let url = format!("sqlite://{}?mode=rwc", path.display());
+		let connection = sea_orm::Database::connect(url).await?;
+		connection
+			.execute_unprepared("PRAGMA foreign_keys = ON;")
+			.await?;
+		migration::Migrator::up(&connection, None).await?;
+		Ok(Self(connection))
+	}
+
+	pub fn connection(&self) -> &DatabaseConnection {
+		&self.0
+	}
+
+	/// The progress of the current (or last) crawl.
+	pub fn checkpoint(&self) -> Checkpoint {
+		Checkpoint::new(self.0.clone())
+	}
+
+	/// Inserts the creature, or replaces the existing creature fetched from the same listing.
+	/// Returns the id of the creature's row.
+	///
+	/// Creatures are keyed by the url of their listing, which is what later crawls look them up by,
+	/// rather than the url linked from the stat block (which may differ).
+	pub async fn upsert_creature(&self, creature: &Creature) -> anyhow::Result<i32> {
+		let txn = self.0.begin().await?;
+
+		let source_book_id = Self::find_or_insert_source_book(&txn, creature.source_book()).await?;
+
+		let url = creature.listing().url().to_string_lossy().to_string();
+		let existing = entity::creature::Entity::find()
+			.filter(entity::creature::Column::Url.eq(&url))
+			.one(&txn)
+			.await?;
+
+		let armor_class = creature.armor_class();
+		let armor_class_sources = armor_class
+			.map(|armor_class| {
+				armor_class
+					.sources()
+					.iter()
+					.map(ToString::to_string)
+					.collect::<Vec<_>>()
+			})
+			.unwrap_or_default();
+		let hit_points = creature.hit_points();
+		let mut model = entity::creature::ActiveModel {
+			url: Set(url),
+			name: Set(creature.name().clone()),
+			source_book_id: Set(source_book_id),
+			challenge_rating: Set(creature.challenge_rating().map(|cr| cr.as_f32() as f64)),
+			experience: Set(creature
+				.challenge()
+				.map(|challenge| challenge.experience() as i32)),
+			kind: Set(creature.kind().clone()),
+			size: Set(creature.size().clone()),
+			alignment: Set(creature.alignment().clone()),
+			armor_class: Set(armor_class.map(|armor_class| armor_class.value() as i32)),
+			armor_class_source: Set(match armor_class_sources.is_empty() {
+				true => None,
+				false => Some(armor_class_sources.join(", ")),
+			}),
+			armor_class_condition: Set(
+				armor_class.and_then(|armor_class| armor_class.condition().cloned())
+			),
+			armor_class_raw: Set(armor_class.and_then(|armor_class| armor_class.raw().cloned())),
+			hit_points: Set(hit_points.map(|(hit_points, _)| *hit_points as i32)),
+			hit_dice: Set(hit_points
+				.and_then(|(_, hit_dice)| hit_dice.as_ref())
+				.map(|dice| dice.to_string())),
+			proficiency_bonus: Set(creature.proficiency_bonus()),
+			passive_perception: Set(creature
+				.senses()
+				.passive_perception()
+				.map(|value| value as i32)),
+			languages: Set(creature.languages().join(", ")),
+			data: Set(Some(serde_json::to_string(creature)?)),
+			data_version: Set(Some(Creature::DATA_VERSION)),
+			listing: Set(Some(serde_json::to_string(creature.listing())?)),
+			..Default::default()
+		};
+		let creature_id = match existing {
+			Some(existing) => {
+				model.id = Set(existing.id);
+				let id = model.update(&txn).await?.id;
+				// The child rows are replaced wholesale, rather than diffed against the new values.
+				entity::speed::Entity::delete_many()
+					.filter(entity::speed::Column::CreatureId.eq(id))
+					.exec(&txn)
+					.await?;
+				entity::attribute::Entity::delete_many()
+					.filter(entity::attribute::Column::CreatureId.eq(id))
+					.exec(&txn)
+					.await?;
+				entity::action::Entity::delete_many()
+					.filter(entity::action::Column::CreatureId.eq(id))
+					.exec(&txn)
+					.await?;
+				entity::armor_class_alternate::Entity::delete_many()
+					.filter(entity::armor_class_alternate::Column::CreatureId.eq(id))
+					.exec(&txn)
+					.await?;
+				id
+			}
+			None => model.insert(&txn).await?.id,
+		};
+
+		let speeds = creature
+			.speeds()
+			.iter()
+			.map(|speed| entity::speed::ActiveModel {
+				creature_id: Set(creature_id),
+				mode: Set(speed.mode().map(|mode| mode.to_string())),
+				distance: Set(speed.distance().map(|distance| distance as i32)),
+				hover: Set(speed.hover()),
+				condition: Set(speed.condition().cloned()),
+				raw: Set(speed.raw().cloned()),
+				..Default::default()
+			})
+			.collect::<Vec<_>>();
+		if !speeds.is_empty() {
+			entity::speed::Entity::insert_many(speeds)
+				.exec(&txn)
+				.await?;
+		}
+
+		let alternate_armor_classes = armor_class
+			.map(|armor_class| armor_class.alternates().as_slice())
+			.unwrap_or_default()
+			.iter()
+			.map(|alternate| {
+				let sources = alternate
+					.sources()
+					.iter()
+					.map(ToString::to_string)
+					.collect::<Vec<_>>();
+				entity::armor_class_alternate::ActiveModel {
+					creature_id: Set(creature_id),
+					value: Set(alternate.value() as i32),
+					source: Set(match sources.is_empty() {
+						true => None,
+						false => Some(sources.join(", ")),
+					}),
+					condition: Set(alternate.condition().clone()),
+					..Default::default()
+				}
+			})
+			.collect::<Vec<_>>();
+		if !alternate_armor_classes.is_empty() {
+			entity::armor_class_alternate::Entity::insert_many(alternate_armor_classes)
+				.exec(&txn)
+				.await?;
+		}
+
+		if let Some(ability_scores) = creature.ability_scores() {
+			let attributes = Ability::all()
+				.into_iter()
+				.map(|ability| {
+					let score = ability_scores.get(ability);
+					entity::attribute::ActiveModel {
+						creature_id: Set(creature_id),
+						ability: Set(ability.abbreviation().to_owned()),
+						score: Set(score.score() as i32),
+						modifier: Set(score.modifier()),
+						..Default::default()
+					}
+				})
+				.collect::<Vec<_>>();
+			entity::attribute::Entity::insert_many(attributes)
+				.exec(&txn)
+				.await?;
+		}
+
+		let mut actions = Vec::new();
+		for (kind, block) in creature.description_blocks() {
+			for (position, entry) in block.entries().iter().enumerate() {
+				actions.push(entity::action::ActiveModel {
+					creature_id: Set(creature_id),
+					block: Set(kind.to_string()),
+					position: Set(position as i32),
+					name: Set(entry.name().clone()),
+					description_html: Set(entry.description().html().clone()),
+					description_text: Set(entry.description().text().clone()),
+					..Default::default()
+				});
+			}
+		}
+		if !actions.is_empty() {
+			entity::action::Entity::insert_many(actions)
+				.exec(&txn)
+				.await?;
+		}
+
+		txn.commit().await?;
+		Ok(creature_id)
+	}
+
+	/// The listing each stored creature was last fetched from, by the url of the listing.
+	/// The listing is `None` if it is unknown (the creature was saved before listings were stored),
+	/// or if the creature was saved with an older [`Creature::DATA_VERSION`], so that it is fetched again.
+	pub async fn stored_listings(
+		&self,
+	) -> anyhow::Result<HashMap<PathBuf, Option<CreatureListing>>> {
+		let rows = entity::creature::Entity::find()
+			.select_only()
+			.column(entity::creature::Column::Url)
+			.column(entity::creature::Column::Listing)
+			.column(entity::creature::Column::DataVersion)
+			.into_tuple::<(String, Option<String>, Option<i32>)>()
+			.all(&self.0)
+			.await?;
+		let mut listings = HashMap::with_capacity(rows.len());
+		for (url, listing, data_version) in rows.into_iter() {
+			let listing = match (listing, data_version) {
+				(Some(listing), Some(Creature::DATA_VERSION)) => {
+					Some(serde_json::from_str::<CreatureListing>(&listing)?)
+				}
+				_ => None,
+			};
+			listings.insert(PathBuf::from(url), listing);
+		}
+		Ok(listings)
+	}
+
+	async fn find_or_insert_source_book<C>(db: &C, name: &str) -> anyhow::Result<i32>
+	where
+		C: ConnectionTrait,
+	{
+		let existing = entity::source_book::Entity::find()
+			.filter(entity::source_book::Column::Name.eq(name))
+			.one(db)
+			.await?;
+		if let Some(source_book) = existing {
+			return Ok(source_book.id);
+		}
+		let model = entity::source_book::ActiveModel {
+			name: Set(name.to_owned()),
+			..Default::default()
+		};
+		Ok(model.insert(db).await?.id)
+	}
+}
@@ -0,0 +1,179 @@
+use super::entity::{crawl_creature, crawl_page, crawl_status::CrawlStatus};
+use crate::dndbeyond::creature_list::CreatureListing;
+use sea_orm::{
+	sea_query::OnConflict, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait,
+	QueryFilter, QuerySelect, Set, TransactionTrait,
+};
+use std::{
+	collections::HashSet,
+	path::{Path, PathBuf},
+};
+
+/// Records which listing pages and creatures a crawl has finished, so that an interrupted crawl can be resumed
+/// without fetching everything again.
+///
+/// When a listing page is finished, the listings on it are recorded as pending creatures.
+/// Resuming a crawl skips the finished pages, and fetches the creatures which are still pending or which failed.
+#[derive(Clone)]
+pub struct Checkpoint(DatabaseConnection);
+impl Checkpoint {
+	pub(super) fn new(connection: DatabaseConnection) -> Self {
+		Self(connection)
+	}
+
+	/// Forgets the progress of the previous crawl, so that the next crawl starts from the beginning.
+	pub async fn clear(&self) -> anyhow::Result<()> {
+		crawl_creature::Entity::delete_many().exec(&self.0).await?;
+		crawl_page::Entity::delete_many().exec(&self.0).await?;
+		Ok(())
+	}
+
+	/// The indices of the listing pages which have been fetched and parsed.
+	pub async fn completed_pages(&self) -> anyhow::Result<HashSet<usize>> {
+		let pages = crawl_page::Entity::find()
+			.filter(crawl_page::Column::Status.eq(CrawlStatus::Done))
+			.all(&self.0)
+			.await?;
+		Ok(pages.into_iter().map(|page| page.page as usize).collect())
+	}
+
+	/// Marks a listing page as finished, recording each of its listings as a pending creature.
+	pub async fn complete_page(
+		&self,
+		page: usize,
+		listings: &[CreatureListing],
+	) -> anyhow::Result<()> {
+		let txn = self.0.begin().await?;
+		if !listings.is_empty() {
+			let mut creatures = Vec::with_capacity(listings.len());
+			for listing in listings.iter() {
+				creatures.push(crawl_creature::ActiveModel {
+					url: Set(Self::creature_key(listing.url())),
+					listing: Set(serde_json::to_string(listing)?),
+					status: Set(CrawlStatus::Pending),
+					error: Set(None),
+				});
+			}
+			// A creature which was already recorded keeps its status, in case it has already been fetched.
+			crawl_creature::Entity::insert_many(creatures)
+				.on_conflict(
+					OnConflict::column(crawl_creature::Column::Url)
+						.update_column(crawl_creature::Column::Listing)
+						.to_owned(),
+				)
+				.exec(&txn)
+				.await?;
+		}
+		Self::set_page_status(&txn, page, CrawlStatus::Done, None).await?;
+		txn.commit().await?;
+		Ok(())
+	}
+
+	/// Marks a listing page as failed, so it is fetched again when resuming.
+	pub async fn fail_page(&self, page: usize, error: &anyhow::Error) -> anyhow::Result<()> {
+		Self::set_page_status(
+			&self.0,
+			page,
+			CrawlStatus::Failed,
+			Some(format!("{error:?}")),
+		)
+		.await
+	}
+
+	async fn set_page_status<C>(
+		db: &C,
+		page: usize,
+		status: CrawlStatus,
+		error: Option<String>,
+	) -> anyhow::Result<()>
+	where
+		C: sea_orm::ConnectionTrait,
+	{
+		let model = crawl_page::ActiveModel {
+			page: Set(page as i32),
+			status: Set(status),
+			error: Set(error),
+		};
+		crawl_page::Entity::insert(model)
+			.on_conflict(
+				OnConflict::column(crawl_page::Column::Page)
+					.update_columns([crawl_page::Column::Status, crawl_page::Column::Error])
+					.to_owned(),
+			)
+			.exec(db)
+			.await?;
+		Ok(())
+	}
+
+	/// The listings of creatures from finished pages which have not been fetched yet, or which failed.
+	/// Creatures skipped by the crawl's filter are not included.
+	pub async fn unfinished_creatures(&self) -> anyhow::Result<Vec<CreatureListing>> {
+		let creatures = crawl_creature::Entity::find()
+			.filter(
+				crawl_creature::Column::Status.is_in([CrawlStatus::Pending, CrawlStatus::Failed]),
+			)
+			.all(&self.0)
+			.await?;
+		let mut listings = Vec::with_capacity(creatures.len());
+		for creature in creatures.into_iter() {
+			listings.push(serde_json::from_str::<CreatureListing>(&creature.listing)?);
+		}
+		Ok(listings)
+	}
+
+	/// Returns true if any listing page failed and has not been completed since.
+	pub async fn has_failed_pages(&self) -> anyhow::Result<bool> {
+		let failed = crawl_page::Entity::find()
+			.filter(crawl_page::Column::Status.eq(CrawlStatus::Failed))
+			.count(&self.0)
+			.await?;
+		Ok(failed > 0)
+	}
+
+	/// The urls of every creature on the listing pages completed by the crawl (whether they were fetched or not).
+	pub async fn listed_urls(&self) -> anyhow::Result<HashSet<PathBuf>> {
+		let urls = crawl_creature::Entity::find()
+			.select_only()
+			.column(crawl_creature::Column::Url)
+			.into_tuple::<String>()
+			.all(&self.0)
+			.await?;
+		Ok(urls.into_iter().map(PathBuf::from).collect())
+	}
+
+	/// Marks a creature as fetched, parsed, and saved to the database.
+	pub async fn complete_creature(&self, url: &Path) -> anyhow::Result<()> {
+		self.set_creature_status(url, CrawlStatus::Done, None).await
+	}
+
+	/// Marks a creature as skipped because it does not match the crawl's filter, so it is not fetched when resuming.
+	pub async fn skip_creature(&self, url: &Path) -> anyhow::Result<()> {
+		self.set_creature_status(url, CrawlStatus::Skipped, None)
+			.await
+	}
+
+	/// Marks a creature as failed, so it is fetched again when resuming.
+	pub async fn fail_creature(&self, url: &Path, error: &anyhow::Error) -> anyhow::Result<()> {
+		self.set_creature_status(url, CrawlStatus::Failed, Some(format!("{error:?}")))
+			.await
+	}
+
+	async fn set_creature_status(
+		&self,
+		url: &Path,
+		status: CrawlStatus,
+		error: Option<String>,
+	) -> anyhow::Result<()> {
+		crawl_creature::Entity::update_many()
+			.col_expr(crawl_creature::Column::Status, status.into())
+			.col_expr(crawl_creature::Column::Error, error.into())
+			.filter(crawl_creature::Column::Url.eq(Self::creature_key(url)))
+			.exec(&self.0)
+			.await?;
+		Ok(())
+	}
+
+	fn creature_key(url: &Path) -> String {
+		url.to_string_lossy().to_string()
+	}
+}
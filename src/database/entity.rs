@@ -0,0 +1,9 @@
+pub mod action;
+pub mod armor_class_alternate;
+pub mod attribute;
+pub mod crawl_creature;
+pub mod crawl_page;
+pub mod crawl_status;
+pub mod creature;
+pub mod source_book;
+pub mod speed;
@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+
+/// A named entry in one of the description blocks of a creature (traits, actions, reactions, etc).
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "action")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	pub creature_id: i32,
+	/// Which block the entry is in (`Traits`, `Actions`, `Legendary Actions`, etc).
+	pub block: String,
+	/// The order of the entry within its block.
+	pub position: i32,
+	pub name: String,
+	pub description_html: String,
+	pub description_text: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::creature::Entity",
+		from = "Column::CreatureId",
+		to = "super::creature::Column::Id",
+		on_delete = "Cascade"
+	)]
+	Creature,
+}
+
+impl Related<super::creature::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Creature.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
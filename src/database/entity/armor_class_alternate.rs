@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+
+/// An armor class a creature only has sometimes, like `15 with mage armor`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "armor_class_alternate")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	pub creature_id: i32,
+	pub value: i32,
+	/// What the armor class comes from, like `natural armor`.
+	pub source: Option<String>,
+	/// When the armor class applies, like `with mage armor`.
+	pub condition: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::creature::Entity",
+		from = "Column::CreatureId",
+		to = "super::creature::Column::Id",
+		on_delete = "Cascade"
+	)]
+	Creature,
+}
+
+impl Related<super::creature::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Creature.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,33 @@
+use sea_orm::entity::prelude::*;
+
+/// One of the six ability scores of a creature.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "attribute")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	pub creature_id: i32,
+	/// The abbreviation of the ability (`STR`, `DEX`, etc).
+	pub ability: String,
+	pub score: i32,
+	pub modifier: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::creature::Entity",
+		from = "Column::CreatureId",
+		to = "super::creature::Column::Id",
+		on_delete = "Cascade"
+	)]
+	Creature,
+}
+
+impl Related<super::creature::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Creature.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
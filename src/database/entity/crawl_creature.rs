@@ -0,0 +1,19 @@
+use super::crawl_status::CrawlStatus;
+use sea_orm::entity::prelude::*;
+
+/// The progress of the current crawl for a creature found in the listings.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "crawl_creature")]
+pub struct Model {
+	#[sea_orm(primary_key, auto_increment = false)]
+	pub url: String,
+	/// The serialized `CreatureListing`, so the creature can be fetched without fetching its listing page again.
+	pub listing: String,
+	pub status: CrawlStatus,
+	pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
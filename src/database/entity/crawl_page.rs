@@ -0,0 +1,18 @@
+use super::crawl_status::CrawlStatus;
+use sea_orm::entity::prelude::*;
+
+/// The progress of the current crawl through a page of the monster listings.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "crawl_page")]
+pub struct Model {
+	/// The index of the listing page (the first page is 0).
+	#[sea_orm(primary_key, auto_increment = false)]
+	pub page: i32,
+	pub status: CrawlStatus,
+	pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
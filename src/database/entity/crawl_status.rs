@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+
+/// How far a crawl got with a listing page or creature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+pub enum CrawlStatus {
+	/// Known about, but not fetched yet.
+	#[sea_orm(string_value = "pending")]
+	Pending,
+	/// Fetched and parsed (and for creatures, saved to the database).
+	#[sea_orm(string_value = "done")]
+	Done,
+	/// Fetching or parsing failed, and should be tried again when resuming.
+	#[sea_orm(string_value = "failed")]
+	Failed,
+	/// Not fetched, because the creature does not match the crawl's filter.
+	#[sea_orm(string_value = "skipped")]
+	Skipped,
+}
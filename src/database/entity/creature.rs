@@ -0,0 +1,91 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "creature")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	/// The D&D Beyond url of the creature's listing, which uniquely identifies it across crawls.
+	#[sea_orm(unique)]
+	pub url: String,
+	pub name: String,
+	pub source_book_id: i32,
+	/// The numeric value of the challenge rating (fractional ratings are stored as 0.125, 0.25, and 0.5).
+	pub challenge_rating: Option<f64>,
+	pub experience: Option<i32>,
+	pub kind: String,
+	pub size: String,
+	pub alignment: String,
+	/// Null if the creature was parsed leniently and its armor class could not be parsed.
+	pub armor_class: Option<i32>,
+	/// What the armor class comes from, like `Chain Shirt, Shield`.
+	pub armor_class_source: Option<String>,
+	/// When the armor class applies, if the creature has others under other conditions (see `armor_class_alternate`).
+	pub armor_class_condition: Option<String>,
+	/// The whole text of the armor class, if any of it was not understood.
+	pub armor_class_raw: Option<String>,
+	/// Null if the creature was parsed leniently and its hit points could not be parsed.
+	pub hit_points: Option<i32>,
+	pub hit_dice: Option<String>,
+	pub proficiency_bonus: Option<i32>,
+	pub passive_perception: Option<i32>,
+	pub languages: String,
+	/// The full creature, serialized as json (see [`crate::export`]).
+	pub data: Option<String>,
+	/// The [`Creature::DATA_VERSION`](crate::creature::Creature::DATA_VERSION) the data was serialized with,
+	/// or `None` if it was saved before versions were recorded.
+	pub data_version: Option<i32>,
+	/// The serialized `CreatureListing` the creature was last fetched from,
+	/// used to find creatures whose listing has changed since.
+	pub listing: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::source_book::Entity",
+		from = "Column::SourceBookId",
+		to = "super::source_book::Column::Id"
+	)]
+	SourceBook,
+	#[sea_orm(has_many = "super::speed::Entity")]
+	Speed,
+	#[sea_orm(has_many = "super::attribute::Entity")]
+	Attribute,
+	#[sea_orm(has_many = "super::action::Entity")]
+	Action,
+	#[sea_orm(has_many = "super::armor_class_alternate::Entity")]
+	ArmorClassAlternate,
+}
+
+impl Related<super::source_book::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::SourceBook.def()
+	}
+}
+
+impl Related<super::speed::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Speed.def()
+	}
+}
+
+impl Related<super::attribute::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Attribute.def()
+	}
+}
+
+impl Related<super::action::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Action.def()
+	}
+}
+
+impl Related<super::armor_class_alternate::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::ArmorClassAlternate.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
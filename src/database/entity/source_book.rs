@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "source_book")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	#[sea_orm(unique)]
+	pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(has_many = "super::creature::Entity")]
+	Creature,
+}
+
+impl Related<super::creature::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Creature.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "speed")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	pub creature_id: i32,
+	/// The movement mode (walk, fly, swim, etc), or none if the speed was not understood (see `raw`).
+	pub mode: Option<String>,
+	/// In feet, or none if the speed was not understood (see `raw`).
+	pub distance: Option<i32>,
+	pub hover: bool,
+	/// When the speed applies, if only sometimes (e.g. `in bat form`).
+	pub condition: Option<String>,
+	/// The whole text of the speed, if any of it was not understood.
+	pub raw: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::creature::Entity",
+		from = "Column::CreatureId",
+		to = "super::creature::Column::Id",
+		on_delete = "Cascade"
+	)]
+	Creature,
+}
+
+impl Related<super::creature::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Creature.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+mod m20261017_000001_create_creature_tables;
+mod m20261017_000002_add_creature_data;
+mod m20261017_000003_create_crawl_checkpoint;
+mod m20261017_000004_add_creature_listing;
+mod m20261017_000005_add_speed_details;
+mod m20261017_000006_add_creature_data_version;
+mod m20261017_000007_make_armor_class_and_hit_points_nullable;
+mod m20261017_000008_make_speed_distance_nullable;
+mod m20261017_000009_add_armor_class_details;
+mod m20261017_000010_store_walking_speed_mode;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+	fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+		vec![
+			Box::new(m20261017_000001_create_creature_tables::Migration),
+			Box::new(m20261017_000002_add_creature_data::Migration),
+			Box::new(m20261017_000003_create_crawl_checkpoint::Migration),
+			Box::new(m20261017_000004_add_creature_listing::Migration),
+			Box::new(m20261017_000005_add_speed_details::Migration),
+			Box::new(m20261017_000006_add_creature_data_version::Migration),
+			Box::new(m20261017_000007_make_armor_class_and_hit_points_nullable::Migration),
+			Box::new(m20261017_000008_make_speed_distance_nullable::Migration),
+			Box::new(m20261017_000009_add_armor_class_details::Migration),
+			Box::new(m20261017_000010_store_walking_speed_mode::Migration),
+		]
+	}
+}
+
+/// Replaces a column of the table with a new definition, copying the value of each row.
+///
+/// Sqlite cannot change the definition of a column, so the existing column is renamed to `previous`,
+/// and its values are copied with `value` into a new column before it is dropped.
+async fn replace_column(
+	manager: &SchemaManager<'_>,
+	table: impl IntoIden,
+	mut column: ColumnDef,
+	previous: impl IntoIden,
+	value: SimpleExpr,
+) -> Result<(), DbErr> {
+	let table = table.into_iden();
+	let previous = previous.into_iden();
+	let name = column.get_column_name();
+	manager
+		.alter_table(
+			Table::alter()
+				.table(table.clone())
+				.rename_column(Alias::new(name.clone()), previous.clone())
+				.to_owned(),
+		)
+		.await?;
+	manager
+		.alter_table(
+			Table::alter()
+				.table(table.clone())
+				.add_column(&mut column)
+				.to_owned(),
+		)
+		.await?;
+	manager
+		.exec_stmt(
+			Query::update()
+				.table(table.clone())
+				.value(Alias::new(name), value)
+				.to_owned(),
+		)
+		.await?;
+	manager
+		.alter_table(Table::alter().table(table).drop_column(previous).to_owned())
+		.await
+}
@@ -0,0 +1,206 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(SourceBook::Table)
+					.if_not_exists()
+					.col(&mut primary_key(SourceBook::Id))
+					.col(
+						ColumnDef::new(SourceBook::Name)
+							.string()
+							.not_null()
+							.unique_key(),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(Creature::Table)
+					.if_not_exists()
+					.col(&mut primary_key(Creature::Id))
+					.col(
+						ColumnDef::new(Creature::Url)
+							.string()
+							.not_null()
+							.unique_key(),
+					)
+					.col(ColumnDef::new(Creature::Name).string().not_null())
+					.col(ColumnDef::new(Creature::SourceBookId).integer().not_null())
+					.col(ColumnDef::new(Creature::ChallengeRating).double())
+					.col(ColumnDef::new(Creature::Experience).integer())
+					.col(ColumnDef::new(Creature::Kind).string().not_null())
+					.col(ColumnDef::new(Creature::Size).string().not_null())
+					.col(ColumnDef::new(Creature::Alignment).string().not_null())
+					.col(ColumnDef::new(Creature::ArmorClass).integer().not_null())
+					.col(ColumnDef::new(Creature::ArmorClassSource).string())
+					.col(ColumnDef::new(Creature::HitPoints).integer().not_null())
+					.col(ColumnDef::new(Creature::HitDice).string())
+					.col(ColumnDef::new(Creature::ProficiencyBonus).integer())
+					.col(ColumnDef::new(Creature::PassivePerception).integer())
+					.col(ColumnDef::new(Creature::Languages).string().not_null())
+					.foreign_key(
+						ForeignKey::create()
+							.from(Creature::Table, Creature::SourceBookId)
+							.to(SourceBook::Table, SourceBook::Id),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(Speed::Table)
+					.if_not_exists()
+					.col(&mut primary_key(Speed::Id))
+					.col(ColumnDef::new(Speed::CreatureId).integer().not_null())
+					.col(ColumnDef::new(Speed::Mode).string())
+					.col(ColumnDef::new(Speed::Distance).integer().not_null())
+					.col(ColumnDef::new(Speed::Subtype).string())
+					.foreign_key(&mut creature_key(Speed::Table, Speed::CreatureId))
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(Attribute::Table)
+					.if_not_exists()
+					.col(&mut primary_key(Attribute::Id))
+					.col(ColumnDef::new(Attribute::CreatureId).integer().not_null())
+					.col(ColumnDef::new(Attribute::Ability).string().not_null())
+					.col(ColumnDef::new(Attribute::Score).integer().not_null())
+					.col(ColumnDef::new(Attribute::Modifier).integer().not_null())
+					.foreign_key(&mut creature_key(Attribute::Table, Attribute::CreatureId))
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(Action::Table)
+					.if_not_exists()
+					.col(&mut primary_key(Action::Id))
+					.col(ColumnDef::new(Action::CreatureId).integer().not_null())
+					.col(ColumnDef::new(Action::Block).string().not_null())
+					.col(ColumnDef::new(Action::Position).integer().not_null())
+					.col(ColumnDef::new(Action::Name).string().not_null())
+					.col(ColumnDef::new(Action::DescriptionHtml).text().not_null())
+					.col(ColumnDef::new(Action::DescriptionText).text().not_null())
+					.foreign_key(&mut creature_key(Action::Table, Action::CreatureId))
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(Action::Table).to_owned())
+			.await?;
+		manager
+			.drop_table(Table::drop().table(Attribute::Table).to_owned())
+			.await?;
+		manager
+			.drop_table(Table::drop().table(Speed::Table).to_owned())
+			.await?;
+		manager
+			.drop_table(Table::drop().table(Creature::Table).to_owned())
+			.await?;
+		manager
+			.drop_table(Table::drop().table(SourceBook::Table).to_owned())
+			.await?;
+		Ok(())
+	}
+}
+
+fn primary_key<T: IntoIden>(column: T) -> ColumnDef {
+	ColumnDef::new(column)
+		.integer()
+		.not_null()
+		.auto_increment()
+		.primary_key()
+		.to_owned()
+}
+
+/// A reference from a child table to its creature, which deletes the child when the creature is deleted.
+fn creature_key<T: IntoIden + 'static>(table: T, column: T) -> ForeignKeyCreateStatement {
+	ForeignKey::create()
+		.from(table, column)
+		.to(Creature::Table, Creature::Id)
+		.on_delete(ForeignKeyAction::Cascade)
+		.to_owned()
+}
+
+#[derive(DeriveIden)]
+enum SourceBook {
+	Table,
+	Id,
+	Name,
+}
+
+#[derive(DeriveIden)]
+enum Creature {
+	Table,
+	Id,
+	Url,
+	Name,
+	SourceBookId,
+	ChallengeRating,
+	Experience,
+	Kind,
+	Size,
+	Alignment,
+	ArmorClass,
+	ArmorClassSource,
+	HitPoints,
+	HitDice,
+	ProficiencyBonus,
+	PassivePerception,
+	Languages,
+}
+
+#[derive(DeriveIden)]
+enum Speed {
+	Table,
+	Id,
+	CreatureId,
+	Mode,
+	Distance,
+	Subtype,
+}
+
+#[derive(DeriveIden)]
+enum Attribute {
+	Table,
+	Id,
+	CreatureId,
+	Ability,
+	Score,
+	Modifier,
+}
+
+#[derive(DeriveIden)]
+enum Action {
+	Table,
+	Id,
+	CreatureId,
+	Block,
+	Position,
+	Name,
+	DescriptionHtml,
+	DescriptionText,
+}
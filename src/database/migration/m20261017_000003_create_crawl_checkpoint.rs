@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(CrawlPage::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(CrawlPage::Page)
+							.integer()
+							.not_null()
+							.primary_key(),
+					)
+					.col(ColumnDef::new(CrawlPage::Status).string_len(16).not_null())
+					.col(ColumnDef::new(CrawlPage::Error).text())
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(CrawlCreature::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(CrawlCreature::Url)
+							.string()
+							.not_null()
+							.primary_key(),
+					)
+					.col(ColumnDef::new(CrawlCreature::Listing).text().not_null())
+					.col(
+						ColumnDef::new(CrawlCreature::Status)
+							.string_len(16)
+							.not_null(),
+					)
+					.col(ColumnDef::new(CrawlCreature::Error).text())
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(CrawlCreature::Table).to_owned())
+			.await?;
+		manager
+			.drop_table(Table::drop().table(CrawlPage::Table).to_owned())
+			.await?;
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum CrawlPage {
+	Table,
+	Page,
+	Status,
+	Error,
+}
+
+#[derive(DeriveIden)]
+enum CrawlCreature {
+	Table,
+	Url,
+	Listing,
+	Status,
+	Error,
+}
@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+/// Replaces the free text `subtype` of speeds with whether the creature can hover,
+/// the condition under which the speed applies, and the text of speeds which were not fully understood.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		// Sqlite can only alter one column at a time.
+		let columns = [
+			ColumnDef::new(Speed::Hover)
+				.boolean()
+				.not_null()
+				.default(false)
+				.to_owned(),
+			ColumnDef::new(Speed::Condition).string().to_owned(),
+			ColumnDef::new(Speed::Raw).string().to_owned(),
+		];
+		for mut column in columns {
+			manager
+				.alter_table(
+					Table::alter()
+						.table(Speed::Table)
+						.add_column(&mut column)
+						.to_owned(),
+				)
+				.await?;
+		}
+
+		// The only subtype which was parsed was `hover`.
+		manager
+			.exec_stmt(
+				Query::update()
+					.table(Speed::Table)
+					.value(Speed::Hover, true)
+					.and_where(Expr::col(Speed::Subtype).eq("hover"))
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Speed::Table)
+					.drop_column(Speed::Subtype)
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Speed::Table)
+					.add_column(ColumnDef::new(Speed::Subtype).string())
+					.to_owned(),
+			)
+			.await?;
+		manager
+			.exec_stmt(
+				Query::update()
+					.table(Speed::Table)
+					.value(Speed::Subtype, "hover")
+					.and_where(Expr::col(Speed::Hover).eq(true))
+					.to_owned(),
+			)
+			.await?;
+		for column in [Speed::Hover, Speed::Condition, Speed::Raw] {
+			manager
+				.alter_table(
+					Table::alter()
+						.table(Speed::Table)
+						.drop_column(column)
+						.to_owned(),
+				)
+				.await?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum Speed {
+	Table,
+	Subtype,
+	Hover,
+	Condition,
+	Raw,
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+/// Records which version of the creature's shape its `data` was serialized with,
+/// so creatures saved with an older shape can be found and fetched again.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Creature::Table)
+					.add_column(ColumnDef::new(Creature::DataVersion).integer())
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Creature::Table)
+					.drop_column(Creature::DataVersion)
+					.to_owned(),
+			)
+			.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum Creature {
+	Table,
+	DataVersion,
+}
@@ -0,0 +1,67 @@
+use super::replace_column;
+use sea_orm_migration::prelude::*;
+
+/// Makes the armor class and hit points of creatures nullable,
+/// so creatures whose armor class or hit points could not be parsed store null rather than 0.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		for (column, previous) in [
+			(Creature::ArmorClass, Creature::PreviousArmorClass),
+			(Creature::HitPoints, Creature::PreviousHitPoints),
+		] {
+			replace_column(
+				manager,
+				Creature::Table,
+				ColumnDef::new(column.clone()).integer().to_owned(),
+				previous.clone(),
+				Expr::col(previous).into(),
+			)
+			.await?;
+			// Every creature has an armor class and hit points, so 0 was only stored when they failed to parse.
+			manager
+				.exec_stmt(
+					Query::update()
+						.table(Creature::Table)
+						.value(column.clone(), Option::<i32>::None)
+						.and_where(Expr::col(column).eq(0))
+						.to_owned(),
+				)
+				.await?;
+		}
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		for (column, previous) in [
+			(Creature::ArmorClass, Creature::PreviousArmorClass),
+			(Creature::HitPoints, Creature::PreviousHitPoints),
+		] {
+			replace_column(
+				manager,
+				Creature::Table,
+				ColumnDef::new(column)
+					.integer()
+					.not_null()
+					.default(0)
+					.to_owned(),
+				previous.clone(),
+				Expr::col(previous).if_null(0),
+			)
+			.await?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden, Clone)]
+enum Creature {
+	Table,
+	ArmorClass,
+	PreviousArmorClass,
+	HitPoints,
+	PreviousHitPoints,
+}
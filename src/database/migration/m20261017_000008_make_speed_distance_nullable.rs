@@ -0,0 +1,42 @@
+use super::replace_column;
+use sea_orm_migration::prelude::*;
+
+/// Makes the distance of speeds nullable, for speeds whose text was not understood (which only keep their `raw` text).
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		replace_column(
+			manager,
+			Speed::Table,
+			ColumnDef::new(Speed::Distance).integer().to_owned(),
+			Speed::PreviousDistance,
+			Expr::col(Speed::PreviousDistance).into(),
+		)
+		.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		replace_column(
+			manager,
+			Speed::Table,
+			ColumnDef::new(Speed::Distance)
+				.integer()
+				.not_null()
+				.default(0)
+				.to_owned(),
+			Speed::PreviousDistance,
+			Expr::col(Speed::PreviousDistance).if_null(0),
+		)
+		.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum Speed {
+	Table,
+	Distance,
+	PreviousDistance,
+}
@@ -0,0 +1,96 @@
+use sea_orm_migration::prelude::*;
+
+/// Stores when a creature's armor class applies, the text of armor classes which were not understood,
+/// and the armor classes the creature has under other conditions.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		// Sqlite can only alter one column at a time.
+		for column in [Creature::ArmorClassCondition, Creature::ArmorClassRaw] {
+			manager
+				.alter_table(
+					Table::alter()
+						.table(Creature::Table)
+						.add_column(ColumnDef::new(column).string())
+						.to_owned(),
+				)
+				.await?;
+		}
+
+		manager
+			.create_table(
+				Table::create()
+					.table(ArmorClassAlternate::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(ArmorClassAlternate::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(ArmorClassAlternate::CreatureId)
+							.integer()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(ArmorClassAlternate::Value)
+							.integer()
+							.not_null(),
+					)
+					.col(ColumnDef::new(ArmorClassAlternate::Source).string())
+					.col(
+						ColumnDef::new(ArmorClassAlternate::Condition)
+							.string()
+							.not_null(),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.from(ArmorClassAlternate::Table, ArmorClassAlternate::CreatureId)
+							.to(Creature::Table, Creature::Id)
+							.on_delete(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(ArmorClassAlternate::Table).to_owned())
+			.await?;
+		for column in [Creature::ArmorClassCondition, Creature::ArmorClassRaw] {
+			manager
+				.alter_table(
+					Table::alter()
+						.table(Creature::Table)
+						.drop_column(column)
+						.to_owned(),
+				)
+				.await?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum Creature {
+	Table,
+	Id,
+	ArmorClassCondition,
+	ArmorClassRaw,
+}
+
+#[derive(DeriveIden)]
+enum ArmorClassAlternate {
+	Table,
+	Id,
+	CreatureId,
+	Value,
+	Source,
+	Condition,
+}
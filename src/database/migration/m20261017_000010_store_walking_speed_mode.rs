@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+/// Stores the mode of walking speeds as `walk`, so that a null mode only means the speed was not understood.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		// Speeds which were not understood have no distance, so the rest without a mode are walking speeds.
+		manager
+			.exec_stmt(
+				Query::update()
+					.table(Speed::Table)
+					.value(Speed::Mode, "walk")
+					.and_where(Expr::col(Speed::Mode).is_null())
+					.and_where(Expr::col(Speed::Distance).is_not_null())
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.exec_stmt(
+				Query::update()
+					.table(Speed::Table)
+					.value(Speed::Mode, Option::<String>::None)
+					.and_where(Expr::col(Speed::Mode).eq("walk"))
+					.to_owned(),
+			)
+			.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum Speed {
+	Table,
+	Mode,
+	Distance,
+}
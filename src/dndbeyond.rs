@@ -2,44 +2,108 @@ use anyhow::Context;
 use async_channel::{Receiver, Sender};
 use futures::Future;
 use std::{
+	path::PathBuf,
 	sync::{Arc, Mutex},
 	task::{Poll, Waker},
 };
 use tokio::task::JoinHandle;
 
+pub mod cache;
+use cache::{CacheEntry, CacheMode, ResponseCache};
 pub mod creature_list;
+pub mod listing_filter;
+pub mod rate_limit;
+use rate_limit::RateLimiter;
+pub mod retry;
+use retry::RetryPolicy;
+pub mod selector_check;
+
+/// The site creatures are scraped from.
+static DNDBEYOND_URL: &str = "https://www.dndbeyond.com";
+/// The directory fetched creature pages are saved to, unless another is given with [`WebpageProvider::with_page_dir`].
+static SAVED_PAGE_DIR: &str = "target/monsters";
 
 pub struct WebpageProvider {
+	base_url: reqwest::Url,
+	page_dir: PathBuf,
 	client: Arc<reqwest::Client>,
+	cache: Arc<Option<ResponseCache>>,
+	retry: Arc<RetryPolicy>,
+	rate_limiter: Arc<Option<RateLimiter>>,
 	send_request: Sender<PendingRequest>,
 	recv_request: Receiver<PendingRequest>,
 }
 impl WebpageProvider {
+	/// Fetches pages from D&D Beyond, signed in with the cookies in `cookies.txt`.
 	pub async fn new() -> anyhow::Result<Self> {
-		let client = Arc::new(Self::build_client().await?);
+		let cookies = Self::read_cookies().await?;
+		Self::for_site(DNDBEYOND_URL.parse()?, cookies)
+	}
+
+	/// Fetches pages from the site at the base url instead of D&D Beyond (e.g. a mock of it in tests),
+	/// sending it the cookies (each `name=value`).
+	pub fn for_site(base_url: reqwest::Url, cookies: Vec<String>) -> anyhow::Result<Self> {
+		let client = Arc::new(Self::build_client(&base_url, cookies)?);
 		let (send_request, recv_request) = async_channel::unbounded();
 		Ok(Self {
+			base_url,
+			page_dir: PathBuf::from(SAVED_PAGE_DIR),
 			client,
+			cache: Arc::new(None),
+			retry: Arc::new(RetryPolicy::default()),
+			rate_limiter: Arc::new(None),
 			send_request,
 			recv_request,
 		})
 	}
 
-	async fn build_client() -> anyhow::Result<reqwest::Client> {
-		let dndbeyond_url = "https://www.dndbeyond.com".parse::<reqwest::Url>()?;
-		let cookie_jar = Self::build_cookie_jar(&dndbeyond_url).await?;
+	/// Stores responses in the on-disk cache, and uses cached responses instead of fetching when possible.
+	/// Must be called before spawning workers.
+	pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+		self.cache = Arc::new(Some(cache));
+		self
+	}
+
+	/// Saves fetched creature pages to the directory instead of `target/monsters`.
+	/// Must be called before spawning workers.
+	pub fn with_page_dir(mut self, page_dir: impl Into<PathBuf>) -> Self {
+		self.page_dir = page_dir.into();
+		self
+	}
+
+	/// The directory fetched creature pages are saved to (see [`creature_list::CreatureListing::fetch_full`]).
+	pub fn page_dir(&self) -> &PathBuf {
+		&self.page_dir
+	}
+
+	/// Sets how failed requests are retried. Must be called before spawning workers.
+	pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+		self.retry = Arc::new(retry);
+		self
+	}
+
+	/// Limits how quickly requests are sent by all workers. Must be called before spawning workers.
+	pub fn with_rate_limit(mut self, rate_limiter: RateLimiter) -> Self {
+		self.rate_limiter = Arc::new(Some(rate_limiter));
+		self
+	}
+
+	fn build_client(
+		base_url: &reqwest::Url,
+		cookies: Vec<String>,
+	) -> anyhow::Result<reqwest::Client> {
+		let cookie_jar = Self::build_cookie_jar(base_url, cookies);
 		Ok(reqwest::Client::builder()
 			.cookie_provider(cookie_jar.clone())
 			.build()?)
 	}
 
-	async fn build_cookie_jar(domain: &reqwest::Url) -> anyhow::Result<Arc<reqwest::cookie::Jar>> {
+	fn build_cookie_jar(domain: &reqwest::Url, cookies: Vec<String>) -> Arc<reqwest::cookie::Jar> {
 		let cookie_jar = Arc::new(reqwest::cookie::Jar::default());
-		let cookies = Self::read_cookies().await?;
 		for cookie in cookies.into_iter() {
 			cookie_jar.add_cookie_str(&cookie, domain);
 		}
-		Ok(cookie_jar)
+		cookie_jar
 	}
 
 	async fn read_cookies() -> anyhow::Result<Vec<String>> {
@@ -59,6 +123,9 @@ impl WebpageProvider {
 		let mut pool_handles = Vec::new();
 		for idx in 0..count {
 			let client = self.client.clone();
+			let cache = self.cache.clone();
+			let retry = self.retry.clone();
+			let rate_limiter = self.rate_limiter.clone();
 			let channel = self.recv_request.clone();
 			let worker_name = NAMES
 				.get(idx)
@@ -71,8 +138,15 @@ impl WebpageProvider {
 						"Fetching {:?}",
 						request.url().as_str()
 					);
-					let result = client.get(request.url().clone()).send().await;
-					request.set_response(result.map_err(|_| FetchFailed(request.url().clone())));
+					let result = Self::fetch_url(
+						&client,
+						cache.as_ref().as_ref(),
+						&retry,
+						rate_limiter.as_ref().as_ref(),
+						request.url(),
+					)
+					.await;
+					request.set_response(result);
 					request.wake();
 				}
 			}));
@@ -80,6 +154,124 @@ impl WebpageProvider {
 		pool_handles
 	}
 
+	async fn fetch_url(
+		client: &reqwest::Client,
+		cache: Option<&ResponseCache>,
+		retry: &RetryPolicy,
+		rate_limiter: Option<&RateLimiter>,
+		url: &reqwest::Url,
+	) -> Result<reqwest::Response, FetchFailed> {
+		let mut cached = None;
+		if let Some(cache) = cache {
+			match cache.get(url).await {
+				Some((entry, body)) if cache.is_fresh(&entry) => {
+					log::debug!("Using cached response for {:?}", url.as_str());
+					return Ok(ResponseCache::to_response(body));
+				}
+				None if cache.mode() == CacheMode::Offline => {
+					return Err(FetchFailed::NotCached(url.clone()));
+				}
+				entry => cached = entry,
+			}
+		}
+
+		let response = Self::send_with_retries(
+			client,
+			retry,
+			rate_limiter,
+			url,
+			cached.as_ref().map(|(entry, _)| entry),
+		)
+		.await?;
+
+		if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+			// The response has no body, so it is only the page if there is a cached copy of it.
+			let (Some(cache), Some((entry, body))) = (cache, cached) else {
+				return Err(FetchFailed::Status(
+					url.clone(),
+					response.status(),
+					"not modified, but the page is not cached".to_owned(),
+				));
+			};
+			if let Err(err) = cache.refresh(url, entry).await {
+				log::warn!("Failed to refresh cache for {:?}: {err:?}", url.as_str());
+			}
+			return Ok(ResponseCache::to_response(body));
+		}
+
+		let Some(cache) = cache else {
+			return Ok(response);
+		};
+
+		// The body has to be read in order to cache it, so the response is rebuilt from the bytes afterwards.
+		let headers = response.headers().clone();
+		let body = response
+			.bytes()
+			.await
+			.map_err(|err| FetchFailed::Transport(url.clone(), err.to_string()))?;
+		if let Err(err) = cache.insert(url, &headers, &body).await {
+			log::warn!("Failed to cache {:?}: {err:?}", url.as_str());
+		}
+		Ok(ResponseCache::to_response(body.to_vec()))
+	}
+
+	/// Sends the request for a url, retrying if the request fails in a way that might succeed later
+	/// (connection errors, rate limiting, server errors).
+	/// Only successful (or `304 Not Modified`) responses are returned, all others are an error.
+	async fn send_with_retries(
+		client: &reqwest::Client,
+		retry: &RetryPolicy,
+		rate_limiter: Option<&RateLimiter>,
+		url: &reqwest::Url,
+		cached: Option<&CacheEntry>,
+	) -> Result<reqwest::Response, FetchFailed> {
+		let mut attempt = 0;
+		loop {
+			if let Some(rate_limiter) = rate_limiter {
+				rate_limiter.acquire(url).await;
+			}
+			let mut builder = client.get(url.clone());
+			if let Some(entry) = cached {
+				builder = ResponseCache::add_conditional_headers(entry, builder);
+			}
+			let (error, retry_after) = match builder.send().await {
+				Ok(response)
+					if response.status().is_success()
+						|| response.status() == reqwest::StatusCode::NOT_MODIFIED =>
+				{
+					return Ok(response);
+				}
+				Ok(response) => {
+					let status = response.status();
+					let retry_after = RetryPolicy::retry_after(response.headers());
+					let body = response.text().await.unwrap_or_default();
+					let error =
+						FetchFailed::Status(url.clone(), status, FetchFailed::snippet(&body));
+					if !RetryPolicy::is_retryable(status) {
+						return Err(error);
+					}
+					(error, retry_after)
+				}
+				Err(err) => (FetchFailed::Transport(url.clone(), err.to_string()), None),
+			};
+			if attempt >= retry.max_retries() {
+				return Err(error);
+			}
+			let delay = retry.delay(attempt, retry_after);
+			attempt += 1;
+			log::warn!(
+				"{error}, retrying in {delay:?} (retry {attempt} of {})",
+				retry.max_retries()
+			);
+			tokio::time::sleep(delay).await;
+		}
+	}
+
+	/// The url of a path on the site (e.g. `/monsters/16921-guard`).
+	pub fn url(&self, path: &str) -> anyhow::Result<reqwest::Url> {
+		Ok(self.base_url.join(path)?)
+	}
+
 	pub fn fetch<TUrl>(&self, url: TUrl) -> anyhow::Result<Request>
 	where
 		TUrl: reqwest::IntoUrl,
@@ -109,10 +301,60 @@ impl PendingRequest {
 }
 
 #[derive(thiserror::Error, Debug, Clone)]
-pub struct FetchFailed(reqwest::Url);
+pub enum FetchFailed {
+	/// The request could not be sent, or the response could not be read.
+	Transport(reqwest::Url, String),
+	/// The site responded with an unsuccessful status, and the start of the response body.
+	Status(reqwest::Url, reqwest::StatusCode, String),
+	/// The cache is offline and the url has not been cached.
+	NotCached(reqwest::Url),
+}
+impl FetchFailed {
+	const SNIPPET_LENGTH: usize = 200;
+
+	pub fn url(&self) -> &reqwest::Url {
+		match self {
+			Self::Transport(url, _) | Self::Status(url, _, _) | Self::NotCached(url) => url,
+		}
+	}
+
+	pub fn status(&self) -> Option<reqwest::StatusCode> {
+		match self {
+			Self::Status(_, status, _) => Some(*status),
+			_ => None,
+		}
+	}
+
+	/// The start of a response body, with whitespace collapsed, for including in error messages.
+	fn snippet(body: &str) -> String {
+		let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+		match body.char_indices().nth(Self::SNIPPET_LENGTH) {
+			Some((idx, _)) => format!("{}...", &body[..idx]),
+			None => body,
+		}
+	}
+}
 impl std::fmt::Display for FetchFailed {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "Failed to fetch url {:?}", self.0)
+		match self {
+			Self::Transport(url, reason) => {
+				write!(f, "Failed to fetch url {:?}: {reason}", url.as_str())
+			}
+			Self::Status(url, status, body) => {
+				write!(
+					f,
+					"Failed to fetch url {:?} ({status}): {body:?}",
+					url.as_str()
+				)
+			}
+			Self::NotCached(url) => {
+				write!(
+					f,
+					"Url {:?} is not cached, and the cache is offline",
+					url.as_str()
+				)
+			}
+		}
 	}
 }
 
@@ -0,0 +1,154 @@
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+use std::{
+	path::PathBuf,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How the cache is used when fetching a url.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+	/// Fresh entries are used as-is, and stale entries are revalidated with the server.
+	Normal,
+	/// Entries are always used (regardless of age), and urls which are not cached fail instead of being fetched.
+	Offline,
+}
+
+/// An on-disk cache of webpage responses.
+///
+/// Each url is stored as two files named by the sha256 hash of the url:
+/// `<hash>.json` holding the metadata of the response (see [`CacheEntry`]), and `<hash>.body` holding its content.
+pub struct ResponseCache {
+	directory: PathBuf,
+	ttl: Duration,
+	mode: CacheMode,
+}
+
+/// The metadata of a cached response.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CacheEntry {
+	pub url: String,
+	pub etag: Option<String>,
+	pub last_modified: Option<String>,
+	/// When the response was last fetched or revalidated, in seconds since the unix epoch.
+	pub fetched_at: u64,
+}
+impl CacheEntry {
+	fn age(&self) -> Duration {
+		Duration::from_secs(now().saturating_sub(self.fetched_at))
+	}
+}
+
+fn now() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|duration| duration.as_secs())
+		.unwrap_or_default()
+}
+
+impl ResponseCache {
+	pub fn new(directory: PathBuf, ttl: Duration, mode: CacheMode) -> Self {
+		Self {
+			directory,
+			ttl,
+			mode,
+		}
+	}
+
+	pub fn mode(&self) -> CacheMode {
+		self.mode
+	}
+
+	fn key(url: &reqwest::Url) -> String {
+		format!("{:x}", Sha256::digest(url.as_str().as_bytes()))
+	}
+
+	fn entry_path(&self, url: &reqwest::Url) -> PathBuf {
+		self.directory.join(format!("{}.json", Self::key(url)))
+	}
+
+	fn body_path(&self, url: &reqwest::Url) -> PathBuf {
+		self.directory.join(format!("{}.body", Self::key(url)))
+	}
+
+	/// Returns true if the entry can be used without asking the server if it has changed.
+	pub fn is_fresh(&self, entry: &CacheEntry) -> bool {
+		self.mode == CacheMode::Offline || entry.age() < self.ttl
+	}
+
+	/// Reads the metadata and content of the cached response for a url, if it exists.
+	pub async fn get(&self, url: &reqwest::Url) -> Option<(CacheEntry, Vec<u8>)> {
+		let entry = tokio::fs::read_to_string(self.entry_path(url)).await.ok()?;
+		let entry = match serde_json::from_str::<CacheEntry>(&entry) {
+			Ok(entry) => entry,
+			Err(err) => {
+				log::warn!(
+					"Ignoring corrupt cache entry for {:?}: {err:?}",
+					url.as_str()
+				);
+				return None;
+			}
+		};
+		let body = tokio::fs::read(self.body_path(url)).await.ok()?;
+		Some((entry, body))
+	}
+
+	/// Saves the content of a successful response to the cache.
+	pub async fn insert(
+		&self,
+		url: &reqwest::Url,
+		headers: &reqwest::header::HeaderMap,
+		body: &[u8],
+	) -> anyhow::Result<()> {
+		let header_value = |name: reqwest::header::HeaderName| {
+			headers
+				.get(name)
+				.and_then(|value| value.to_str().ok())
+				.map(|value| value.to_owned())
+		};
+		let entry = CacheEntry {
+			url: url.as_str().to_owned(),
+			etag: header_value(reqwest::header::ETAG),
+			last_modified: header_value(reqwest::header::LAST_MODIFIED),
+			fetched_at: now(),
+		};
+		tokio::fs::create_dir_all(&self.directory).await?;
+		tokio::fs::write(self.body_path(url), body)
+			.await
+			.context("writing cached body")?;
+		self.write_entry(url, &entry).await
+	}
+
+	/// Marks a cached response as up-to-date, after the server has said it was not modified.
+	pub async fn refresh(&self, url: &reqwest::Url, mut entry: CacheEntry) -> anyhow::Result<()> {
+		entry.fetched_at = now();
+		self.write_entry(url, &entry).await
+	}
+
+	async fn write_entry(&self, url: &reqwest::Url, entry: &CacheEntry) -> anyhow::Result<()> {
+		tokio::fs::write(self.entry_path(url), serde_json::to_string_pretty(entry)?)
+			.await
+			.context("writing cache entry")?;
+		Ok(())
+	}
+
+	/// Adds the conditional headers to a request, so the server can respond with `304 Not Modified`
+	/// if the cached copy is still current.
+	pub fn add_conditional_headers(
+		entry: &CacheEntry,
+		mut request: reqwest::RequestBuilder,
+	) -> reqwest::RequestBuilder {
+		if let Some(etag) = &entry.etag {
+			request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+		}
+		if let Some(last_modified) = &entry.last_modified {
+			request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+		}
+		request
+	}
+
+	/// Creates a response from cached content, so it can be consumed like one from the network.
+	pub fn to_response(body: Vec<u8>) -> reqwest::Response {
+		reqwest::Response::from(http::Response::new(body))
+	}
+}
@@ -1,189 +1,211 @@
 use super::WebpageProvider;
-use crate::creature::Creature;
+use crate::{
+	creature::{challenge_rating::ChallengeRating, diagnostic::ParseMode, Creature},
+	database::checkpoint::Checkpoint,
+	selectors::{ListingSelectors, Selectors},
+	utility::{ParseError, Scope},
+};
 use anyhow::Context;
-use std::{ops::Range, path::PathBuf, str::FromStr, sync::Arc};
+use std::{
+	collections::HashSet,
+	ops::Range,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+/// The selectors for a page of the monster listing, which may have been overridden by the user (see [`Selectors`]).
+fn selectors() -> &'static ListingSelectors {
+	&Selectors::get().listing
+}
 
-pub struct PageIter(Range<usize>);
+pub struct PageIter {
+	listing_url: reqwest::Url,
+	pages: Range<usize>,
+}
 impl PageIter {
-	pub fn with_range(range: Range<usize>) -> Self {
-		Self(range)
+	pub fn with_range(provider: &WebpageProvider, range: Range<usize>) -> anyhow::Result<Self> {
+		Ok(Self {
+			listing_url: Self::listing_url(provider)?,
+			pages: range,
+		})
 	}
 
 	pub async fn new(page_idx: usize, provider: Arc<WebpageProvider>) -> anyhow::Result<Self> {
+		let listing_url = Self::listing_url(&provider)?;
 		let max_page_count = {
-			let url = reqwest::Url::parse(Self::base_url())?;
-			let response = provider.fetch(url)?.await?;
+			let response = provider.fetch(listing_url.clone())?.await?;
 			let body = response.text().await?;
-			Self::find_max_page_count(&body)?
+			Self::find_max_page_count(listing_url.as_str(), &body)?
 		};
-		Ok(Self(page_idx..max_page_count))
+		Ok(Self {
+			listing_url,
+			pages: page_idx..max_page_count,
+		})
 	}
 
-	fn base_url() -> &'static str {
-		"https://www.dndbeyond.com/monsters"
+	fn listing_url(provider: &WebpageProvider) -> anyhow::Result<reqwest::Url> {
+		provider.url("/monsters")
 	}
 
 	fn next_url(&self) -> String {
-		format!("{}?page={}&sort=cr", Self::base_url(), self.0.start + 1)
-	}
-
-	fn find_max_page_count(html: &str) -> anyhow::Result<usize> {
-		static PAGINATION_SELECTOR: [&'static str; 10] = [
-			"body",
-			"#site",
-			"#site-main",
-			".container",
-			"#content",
-			".primary-content",
-			".listing-container",
-			".listing-footer",
-			".b-pagination",
-			"ul.b-pagination-list",
-		];
-		let html = scraper::Html::parse_document(&html);
-
-		let selector = scraper::Selector::parse(&PAGINATION_SELECTOR.join(" > ")).unwrap();
-		let pagination_list = html.select(&selector).next().unwrap();
-
-		let selector_item = scraper::Selector::parse(r#"li.b-pagination-item"#).unwrap();
-		let paginator_item_count = pagination_list.select(&selector_item).count();
+		format!("{}?page={}&sort=cr", self.listing_url, self.pages.start + 1)
+	}
+
+	pub(crate) fn find_max_page_count(url: &str, html: &str) -> anyhow::Result<usize> {
+		let html = scraper::Html::parse_document(html);
+
+		let pagination_list =
+			Scope::root(&html, url).select(&selectors().pagination, "pagination")?;
+
+		let pagination_items = pagination_list.select_all(&selectors().pagination_item);
 		// we need the last true page item, aka the second to last pagination item (last is the `Next` button).
 		// Second to last is `length - 2`, because last is `length - 1`.
-		let last_page_item = pagination_list
-			.select(&selector_item)
-			.nth(paginator_item_count - 2)
-			.unwrap();
-
-		let selector_label = scraper::Selector::parse(r#"a.b-pagination-item"#).unwrap();
-		let last_page_label = last_page_item.select(&selector_label).next().unwrap();
-		let last_page = last_page_label.inner_html().parse::<usize>()?;
+		let last_page_item = pagination_items
+			.len()
+			.checked_sub(2)
+			.and_then(|idx| pagination_items.get(idx))
+			.ok_or_else(|| ParseError::MissingElement {
+				url: url.to_owned(),
+				field: "last page",
+				selector: selectors().pagination_item.as_str().to_owned(),
+			})?;
+
+		let last_page_label = last_page_item.select(&selectors().pagination_link, "last page")?;
+		let text = last_page_label.inner_html();
+		let last_page = text
+			.parse::<usize>()
+			.map_err(|_| last_page_label.invalid_text("last page", text.clone()))?;
 
 		Ok(last_page)
 	}
 
 	pub fn next(&mut self) -> Option<String> {
-		if self.0.start < self.0.end {
+		self.next_page().map(|(_, url)| url)
+	}
+
+	/// Returns the index of the next page (starting at 0) and its url.
+	pub fn next_page(&mut self) -> Option<(usize, String)> {
+		if self.pages.start < self.pages.end {
+			let page_idx = self.pages.start;
 			let url = self.next_url();
-			self.0.start += 1;
-			Some(url)
+			self.pages.start += 1;
+			Some((page_idx, url))
 		} else {
 			None
 		}
 	}
 }
 
-pub struct CreatureListingPage(scraper::Html);
-impl From<&String> for CreatureListingPage {
-	fn from(body: &String) -> Self {
-		Self(scraper::Html::parse_document(body))
-	}
+pub struct CreatureListingPage {
+	url: String,
+	html: scraper::Html,
 }
 impl CreatureListingPage {
-	pub fn list<'doc>(&'doc self) -> CreatureList<'doc> {
-		let s_primary_content = scraper::Selector::parse(
-			r#"body > #site > #site-main > .container > #content > .primary-content"#,
-		)
-		.unwrap();
-		let primary_content = self.0.select(&s_primary_content).next().unwrap();
+	pub fn new(url: &reqwest::Url, body: &str) -> Self {
+		Self {
+			url: url.as_str().to_owned(),
+			html: scraper::Html::parse_document(body),
+		}
+	}
 
-		let s_listings =
-			scraper::Selector::parse(r#".listing-container > .listing-body > ul.listing"#).unwrap();
-		let listings = primary_content.select(&s_listings).next().unwrap();
-		CreatureList(listings)
+	pub fn list(&self) -> Result<CreatureList<'_>, ParseError> {
+		let primary_content =
+			Scope::root(&self.html, &self.url).select(&selectors().page_content, "page content")?;
+		let listings = primary_content.select(&selectors().listing, "monster listing")?;
+		Ok(CreatureList(listings))
 	}
 }
 
-pub struct CreatureList<'doc>(scraper::ElementRef<'doc>);
+pub struct CreatureList<'doc>(Scope<'doc>);
 impl<'doc> CreatureList<'doc> {
 	pub fn children(&self) -> Vec<CreatureRowHtml<'doc>> {
-		let s_info = scraper::Selector::parse(r#".info"#).unwrap();
-		self.0.select(&s_info).map(CreatureRowHtml::from).collect()
+		let rows = self.0.select_all(&selectors().row);
+		rows.into_iter().map(CreatureRowHtml::from).collect()
 	}
 }
 
-pub struct CreatureRowHtml<'doc>(scraper::ElementRef<'doc>);
-impl<'doc> From<scraper::ElementRef<'doc>> for CreatureRowHtml<'doc> {
-	fn from(html: scraper::ElementRef<'doc>) -> Self {
+pub struct CreatureRowHtml<'doc>(Scope<'doc>);
+impl<'doc> From<Scope<'doc>> for CreatureRowHtml<'doc> {
+	fn from(html: Scope<'doc>) -> Self {
 		Self(html)
 	}
 }
 impl<'doc> CreatureRowHtml<'doc> {
-	pub fn title_block(&self) -> TitleBlock<'doc> {
-		let s_name_src = scraper::Selector::parse(r#".monster-name"#).unwrap();
-		let name_and_src = self.0.select(&s_name_src).next().unwrap();
-		TitleBlock(name_and_src)
+	pub fn title_block(&self) -> Result<TitleBlock<'doc>, ParseError> {
+		Ok(TitleBlock(
+			self.0.select(&selectors().title, "name and source")?,
+		))
 	}
 
-	pub fn challenge_rating(&self) -> Option<usize> {
-		let s_cr = scraper::Selector::parse(r#".monster-challenge > span"#).unwrap();
-		let cr = self.0.select(&s_cr).next();
-		cr.map(|cr| cr.inner_html().parse::<usize>().ok()).flatten()
+	pub fn challenge_rating(&self) -> Result<Option<ChallengeRating>, ParseError> {
+		let cr = self.0.select_optional(&selectors().challenge_rating);
+		Ok(cr.and_then(|cr| cr.inner_html().parse::<ChallengeRating>().ok()))
 	}
 
-	pub fn kind(&self) -> String {
-		let s_type = scraper::Selector::parse(r#".monster-type > span.type"#).unwrap();
-		let kind_html = self.0.select(&s_type).next().unwrap();
-		kind_html.inner_html()
+	pub fn kind(&self) -> Result<String, ParseError> {
+		let kind_html = self.0.select(&selectors().kind, "type")?;
+		Ok(kind_html.inner_html())
 	}
 
-	pub fn size(&self) -> String {
-		let s_size = scraper::Selector::parse(r#".monster-size > span"#).unwrap();
-		let size = self.0.select(&s_size).next().unwrap();
-		size.inner_html()
+	pub fn size(&self) -> Result<String, ParseError> {
+		let size = self.0.select(&selectors().size, "size")?;
+		Ok(size.inner_html())
 	}
 }
 
-pub struct TitleBlock<'doc>(scraper::ElementRef<'doc>);
+pub struct TitleBlock<'doc>(Scope<'doc>);
 impl<'doc> TitleBlock<'doc> {
-	pub fn expand(self) -> (String, String, PathBuf) {
-		let source_book = self.source_book();
-		let name_link = self.name_link();
-		(name_link.name(), source_book, name_link.url())
+	pub fn expand(self) -> Result<(String, String, PathBuf), ParseError> {
+		let source_book = self.source_book()?;
+		let name_link = self.name_link()?;
+		Ok((name_link.name(), source_book, name_link.url()?))
 	}
 
-	pub fn name_link(&self) -> TitleBlockNameLink {
-		let s_name = scraper::Selector::parse(r#"span.name > a.link"#).unwrap();
-		TitleBlockNameLink(self.0.select(&s_name).next().unwrap())
+	pub fn name_link(&self) -> Result<TitleBlockNameLink<'doc>, ParseError> {
+		Ok(TitleBlockNameLink(
+			self.0.select(&selectors().name_link, "name")?,
+		))
 	}
 
-	pub fn source_book(&self) -> String {
-		let s_source = scraper::Selector::parse(r#"span.source"#).unwrap();
-		let source = self.0.select(&s_source).next().unwrap();
-		source.inner_html()
+	pub fn source_book(&self) -> Result<String, ParseError> {
+		let source = self.0.select(&selectors().source_book, "source book")?;
+		Ok(source.inner_html())
 	}
 }
 
-pub struct TitleBlockNameLink<'doc>(scraper::ElementRef<'doc>);
+pub struct TitleBlockNameLink<'doc>(Scope<'doc>);
 impl<'doc> TitleBlockNameLink<'doc> {
 	pub fn name(&self) -> String {
 		self.0.inner_html()
 	}
 
-	pub fn url(&self) -> PathBuf {
-		PathBuf::from(self.0.value().attr("href").unwrap())
+	pub fn url(&self) -> Result<PathBuf, ParseError> {
+		Ok(PathBuf::from(self.0.attr("href", "creature url")?))
 	}
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CreatureListing {
 	pub(crate) name: String,
 	pub(crate) source_book: String,
 	pub(crate) url: PathBuf,
-	pub(crate) challenge_rating: Option<usize>,
+	pub(crate) challenge_rating: Option<ChallengeRating>,
 	pub(crate) kind: String,
 	pub(crate) size: String,
 }
-impl<'doc> From<CreatureRowHtml<'doc>> for CreatureListing {
-	fn from(row: CreatureRowHtml<'doc>) -> Self {
-		let (name, source_book, url) = row.title_block().expand();
-		Self {
+impl<'doc> TryFrom<CreatureRowHtml<'doc>> for CreatureListing {
+	type Error = ParseError;
+
+	fn try_from(row: CreatureRowHtml<'doc>) -> Result<Self, Self::Error> {
+		let (name, source_book, url) = row.title_block()?.expand()?;
+		Ok(Self {
 			name,
 			url,
 			source_book,
-			challenge_rating: row.challenge_rating(),
-			kind: row.kind(),
-			size: row.size(),
-		}
+			challenge_rating: row.challenge_rating()?,
+			kind: row.kind()?,
+			size: row.size()?,
+		})
 	}
 }
 impl CreatureListing {
@@ -191,62 +213,171 @@ impl CreatureListing {
 		&self.name
 	}
 
-	pub async fn fetch_full(self, provider: &Arc<WebpageProvider>) -> anyhow::Result<Creature> {
-		let full_url = format!("https://www.dndbeyond.com{}", self.url.to_str().unwrap());
+	pub fn url(&self) -> &PathBuf {
+		&self.url
+	}
+
+	pub fn source_book(&self) -> &String {
+		&self.source_book
+	}
+
+	pub fn challenge_rating(&self) -> Option<ChallengeRating> {
+		self.challenge_rating
+	}
+
+	pub fn kind(&self) -> &String {
+		&self.kind
+	}
+
+	pub fn size(&self) -> &String {
+		&self.size
+	}
+
+	/// Returns true if the fields shown in the listing (source, challenge rating, type, or size)
+	/// are different from a previous listing of the same creature.
+	pub fn has_changed(&self, previous: &Self) -> bool {
+		self.source_book != previous.source_book
+			|| self.challenge_rating != previous.challenge_rating
+			|| self.kind != previous.kind
+			|| self.size != previous.size
+	}
+
+	/// The path the creature's page is saved to in the directory when it is fetched (`<page_dir>/<name>.html`).
+	pub fn saved_page_path(&self, page_dir: &Path) -> anyhow::Result<PathBuf> {
+		let file_name = self
+			.url
+			.file_name()
+			.and_then(|name| name.to_str())
+			.with_context(|| format!("creature url {:?} has no file name", self.url))?;
+		Ok(page_dir.join(format!("{file_name}.html")))
+	}
+
+	pub async fn fetch_full(
+		self,
+		provider: &Arc<WebpageProvider>,
+		mode: ParseMode,
+	) -> anyhow::Result<Creature> {
+		let full_url = provider.url(&self.url.to_string_lossy())?;
+		let tmp_output_path = self.saved_page_path(provider.page_dir())?;
 		let response = provider
 			.fetch(full_url)?
 			.await
 			.context(format!("fetching creature {:?}", self.name))?;
 		let body = response.text().await?;
 
-		let tmp_output_path = PathBuf::from_str(&format!(
-			"target/monsters/{}.html",
-			self.url.file_name().unwrap().to_str().unwrap()
-		))?;
 		if let Some(parent) = tmp_output_path.parent() {
 			tokio::fs::create_dir_all(parent).await?;
 		}
-		tokio::fs::write(tmp_output_path, &body).await?;
+		tokio::fs::write(&tmp_output_path, &body).await?;
+		// Save the listing next to the page, so the page can be parsed again without the catalogue.
+		tokio::fs::write(
+			Self::sidecar_path(&tmp_output_path),
+			serde_json::to_string_pretty(&self)?,
+		)
+		.await?;
 
-		Creature::parse(self, body)
+		Creature::parse_with_mode(self, body, mode)
+	}
+
+	/// The path of the file which stores the listing for a saved creature page (`guard.html` => `guard.listing.json`).
+	pub fn sidecar_path(html_path: &Path) -> PathBuf {
+		html_path.with_extension("listing.json")
+	}
+
+	/// Parses a creature page saved by [`CreatureListing::fetch_full`],
+	/// using the listing saved in its sidecar file.
+	pub async fn parse_saved(html_path: &Path, mode: ParseMode) -> anyhow::Result<Creature> {
+		let sidecar_path = Self::sidecar_path(html_path);
+		let sidecar = tokio::fs::read_to_string(&sidecar_path)
+			.await
+			.with_context(|| format!("reading listing {sidecar_path:?}"))?;
+		let listing = serde_json::from_str::<Self>(&sidecar)
+			.with_context(|| format!("parsing listing {sidecar_path:?}"))?;
+		let body = tokio::fs::read_to_string(html_path)
+			.await
+			.with_context(|| format!("reading page {html_path:?}"))?;
+		Creature::parse_with_mode(listing, body, mode)
+			.with_context(|| format!("parsing page {html_path:?}"))
 	}
 
 	/// Queries all of the pages in the monster catalogue,
 	/// sending the public metadata (each row in the listings of each page) to the channel.
+	///
+	/// If a checkpoint is provided, pages it has already completed are skipped,
+	/// and the outcome of each page fetched is recorded to it.
 	pub async fn fetch_all(
 		provider: Arc<WebpageProvider>,
 		send_creature: async_channel::Sender<Self>,
 		page_range: Option<Range<usize>>,
+		checkpoint: Option<Checkpoint>,
 	) -> anyhow::Result<()> {
 		let mut parsing_tasks = Vec::new();
 
 		let mut page_iter = match page_range {
-			Some(range) => PageIter::with_range(range),
+			Some(range) => PageIter::with_range(&provider, range)?,
 			None => PageIter::new(0, provider.clone()).await?,
 		};
 
+		let completed_pages = match &checkpoint {
+			Some(checkpoint) => checkpoint.completed_pages().await?,
+			None => HashSet::new(),
+		};
+
 		// Iterate over all of the pages that exist
-		while let Some(url_string) = page_iter.next() {
+		while let Some((page_idx, url_string)) = page_iter.next_page() {
+			if completed_pages.contains(&page_idx) {
+				log::debug!(
+					"Skipping listing page {page_idx}, which was completed by a previous crawl"
+				);
+				continue;
+			}
 			let async_provider = provider.clone();
 			let send_channel = send_creature.clone();
+			let checkpoint = checkpoint.clone();
 			parsing_tasks.push(tokio::task::spawn(async move {
-				let url = reqwest::Url::parse(&url_string)?;
-				let response = async_provider.fetch(url)?.await?;
-				let body = response.text().await?;
-
-				let page = CreatureListingPage(scraper::Html::parse_document(&body));
-				let list_elements = page.list().children();
-				for element in list_elements.into_iter() {
-					let _ = send_channel.try_send(CreatureListing::from(element));
+				let listings = match Self::fetch_page(&async_provider, &url_string).await {
+					Ok(listings) => listings,
+					Err(err) => {
+						if let Some(checkpoint) = &checkpoint {
+							checkpoint.fail_page(page_idx, &err).await?;
+						}
+						return Err(err);
+					}
+				};
+				if let Some(checkpoint) = &checkpoint {
+					checkpoint.complete_page(page_idx, &listings).await?;
+				}
+				for listing in listings.into_iter() {
+					let _ = send_channel.try_send(listing);
 				}
-
 				Ok(()) as anyhow::Result<()>
 			}));
 		}
-		futures::future::join_all(parsing_tasks).await;
+		for result in futures::future::join_all(parsing_tasks).await {
+			if let Err(err) = result? {
+				log::error!("{err:?}");
+			}
+		}
 		Ok(())
 	}
 
+	async fn fetch_page(provider: &WebpageProvider, url_string: &str) -> anyhow::Result<Vec<Self>> {
+		let url = reqwest::Url::parse(url_string)?;
+		let response = provider.fetch(url.clone())?.await?;
+		let body = response.text().await?;
+
+		let page = CreatureListingPage::new(&url, &body);
+		let mut listings = Vec::new();
+		// A row which can't be parsed is skipped, rather than losing every other creature on the page.
+		for row in page.list()?.children().into_iter() {
+			match CreatureListing::try_from(row) {
+				Ok(listing) => listings.push(listing),
+				Err(err) => log::error!("Skipping monster listing: {err}"),
+			}
+		}
+		Ok(listings)
+	}
+
 	pub async fn fetch_pages(
 		provider: Arc<WebpageProvider>,
 		page_range: Option<Range<usize>>,
@@ -254,7 +385,7 @@ impl CreatureListing {
 		let mut parsing_tasks = Vec::new();
 
 		let mut page_iter = match page_range {
-			Some(range) => PageIter::with_range(range),
+			Some(range) => PageIter::with_range(&provider, range)?,
 			None => PageIter::new(0, provider.clone()).await?,
 		};
 
@@ -0,0 +1,126 @@
+use super::creature_list::CreatureListing;
+use crate::creature::challenge_rating::ChallengeRating;
+
+/// Selects which creatures in the monster listings have their full pages fetched.
+///
+/// A listing must match every kind of filter that has been set. For filters with several values
+/// (e.g. more than one name pattern or size), the listing must match at least one of the values.
+/// A filter with no values matches every listing.
+#[derive(Debug, Default, Clone)]
+pub struct ListingFilter {
+	name_globs: Vec<glob::Pattern>,
+	name_regexes: Vec<regex::Regex>,
+	min_challenge_rating: Option<ChallengeRating>,
+	max_challenge_rating: Option<ChallengeRating>,
+	kinds: Vec<String>,
+	sizes: Vec<String>,
+	source_books: Vec<String>,
+}
+impl ListingFilter {
+	/// Matches creatures whose name matches a glob pattern (e.g. `"Young * Dragon"`), ignoring case.
+	pub fn with_name_glob(mut self, pattern: &str) -> anyhow::Result<Self> {
+		self.name_globs.push(glob::Pattern::new(pattern)?);
+		Ok(self)
+	}
+
+	/// Matches creatures whose name contains a match for the regular expression.
+	pub fn with_name_regex(mut self, pattern: &str) -> anyhow::Result<Self> {
+		self.name_regexes.push(regex::Regex::new(pattern)?);
+		Ok(self)
+	}
+
+	/// Matches creatures whose challenge rating is within the (inclusive) bounds.
+	/// Creatures without a challenge rating never match if either bound is set.
+	/// Fails if the minimum is greater than the maximum, since no creature could match.
+	pub fn with_challenge_rating(
+		mut self,
+		min: Option<ChallengeRating>,
+		max: Option<ChallengeRating>,
+	) -> anyhow::Result<Self> {
+		if let (Some(min), Some(max)) = (min, max) {
+			if min > max {
+				anyhow::bail!(
+					"the minimum challenge rating ({min}) is greater than the maximum ({max})"
+				);
+			}
+		}
+		self.min_challenge_rating = min;
+		self.max_challenge_rating = max;
+		Ok(self)
+	}
+
+	/// Matches creatures of the type (e.g. `"Humanoid"`), ignoring case.
+	pub fn with_kind(mut self, kind: impl Into<String>) -> Self {
+		self.kinds.push(kind.into());
+		self
+	}
+
+	/// Matches creatures of the size (e.g. `"Medium"`), ignoring case.
+	pub fn with_size(mut self, size: impl Into<String>) -> Self {
+		self.sizes.push(size.into());
+		self
+	}
+
+	/// Matches creatures from the source book (e.g. `"Monster Manual"`), ignoring case.
+	pub fn with_source_book(mut self, source_book: impl Into<String>) -> Self {
+		self.source_books.push(source_book.into());
+		self
+	}
+
+	/// Returns true if no filters have been set, so every listing matches.
+	pub fn is_empty(&self) -> bool {
+		self.name_globs.is_empty()
+			&& self.name_regexes.is_empty()
+			&& self.min_challenge_rating.is_none()
+			&& self.max_challenge_rating.is_none()
+			&& self.kinds.is_empty()
+			&& self.sizes.is_empty()
+			&& self.source_books.is_empty()
+	}
+
+	pub fn matches(&self, listing: &CreatureListing) -> bool {
+		self.matches_name(listing.name())
+			&& self.matches_challenge_rating(listing.challenge_rating())
+			&& Self::matches_any(&self.kinds, listing.kind())
+			&& Self::matches_any(&self.sizes, listing.size())
+			&& Self::matches_any(&self.source_books, listing.source_book())
+	}
+
+	fn matches_name(&self, name: &str) -> bool {
+		if self.name_globs.is_empty() && self.name_regexes.is_empty() {
+			return true;
+		}
+		let options = glob::MatchOptions {
+			case_sensitive: false,
+			..Default::default()
+		};
+		self.name_globs
+			.iter()
+			.any(|pattern| pattern.matches_with(name, options))
+			|| self.name_regexes.iter().any(|regex| regex.is_match(name))
+	}
+
+	fn matches_challenge_rating(&self, challenge_rating: Option<ChallengeRating>) -> bool {
+		if self.min_challenge_rating.is_none() && self.max_challenge_rating.is_none() {
+			return true;
+		}
+		let Some(challenge_rating) = challenge_rating else {
+			return false;
+		};
+		// A rating matches if it is within whichever of the bounds are set.
+		let below_min = self
+			.min_challenge_rating
+			.is_some_and(|min| challenge_rating < min);
+		let above_max = self
+			.max_challenge_rating
+			.is_some_and(|max| challenge_rating > max);
+		!below_min && !above_max
+	}
+
+	fn matches_any(values: &[String], value: &str) -> bool {
+		values.is_empty()
+			|| values
+				.iter()
+				.any(|expected| expected.eq_ignore_ascii_case(value))
+	}
+}
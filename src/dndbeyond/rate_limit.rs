@@ -0,0 +1,60 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+use tokio::time::Instant;
+
+/// Limits how many requests are sent to each host per second, shared by all of the workers of a [`WebpageProvider`](super::WebpageProvider).
+///
+/// Each host has a bucket of tokens which refills at `rate` tokens per second, holding at most `burst` tokens.
+/// Sending a request takes a token from the bucket, waiting for the bucket to refill if it is empty.
+/// Time is measured by tokio's clock, so it can be paused in tests.
+pub struct RateLimiter {
+	rate: f64,
+	burst: f64,
+	buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+	tokens: f64,
+	last_refill: Instant,
+}
+
+impl RateLimiter {
+	/// Creates a limiter allowing `rate` requests per second to each host, and up to `burst` requests at once.
+	pub fn new(rate: f64, burst: u32) -> Self {
+		assert!(rate > 0.0, "rate limit must be positive");
+		Self {
+			rate,
+			burst: burst.max(1) as f64,
+			buckets: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Waits until a request to the url's host is allowed.
+	pub async fn acquire(&self, url: &reqwest::Url) {
+		let host = url.host_str().unwrap_or_default().to_owned();
+		while let Some(wait) = self.try_acquire(&host) {
+			log::trace!("Rate limited requests to {host:?}, waiting {wait:?}");
+			tokio::time::sleep(wait).await;
+		}
+	}
+
+	/// Takes a token for the host if one is available, otherwise returns how long until one will be.
+	fn try_acquire(&self, host: &str) -> Option<Duration> {
+		let mut buckets = self.buckets.lock().unwrap();
+		let now = Instant::now();
+		let bucket = buckets.entry(host.to_owned()).or_insert(Bucket {
+			tokens: self.burst,
+			last_refill: now,
+		});
+
+		let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+		bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+		bucket.last_refill = now;
+
+		if bucket.tokens >= 1.0 {
+			bucket.tokens -= 1.0;
+			None
+		} else {
+			Some(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+		}
+	}
+}
@@ -0,0 +1,80 @@
+use rand::Rng;
+use std::time::{Duration, SystemTime};
+
+/// How many times a failed request is retried, and how long to wait between attempts.
+///
+/// The wait before each retry doubles from `base_delay` (up to `max_delay`), with random jitter so that
+/// workers which failed at the same time don't all retry at the same time.
+/// If the site responds with a `Retry-After` header, that delay is used instead (also no longer than `max_delay`,
+/// so a huge value or a far-off date doesn't stall a worker).
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+	max_retries: u32,
+	base_delay: Duration,
+	max_delay: Duration,
+}
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self {
+			max_retries: 3,
+			base_delay: Duration::from_millis(500),
+			max_delay: Duration::from_secs(30),
+		}
+	}
+}
+impl RetryPolicy {
+	pub fn new(max_retries: u32, base_delay: Duration, max_delay: Duration) -> Self {
+		Self {
+			max_retries,
+			base_delay,
+			max_delay,
+		}
+	}
+
+	/// A policy which never retries.
+	pub fn none() -> Self {
+		Self {
+			max_retries: 0,
+			..Default::default()
+		}
+	}
+
+	pub fn max_retries(&self) -> u32 {
+		self.max_retries
+	}
+
+	/// Returns true if a response with this status might succeed if requested again.
+	pub fn is_retryable(status: reqwest::StatusCode) -> bool {
+		status == reqwest::StatusCode::TOO_MANY_REQUESTS
+			|| status == reqwest::StatusCode::REQUEST_TIMEOUT
+			|| status.is_server_error()
+	}
+
+	/// The time to wait before the retry following the attempt (where the first attempt is 0).
+	pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+		if let Some(retry_after) = retry_after {
+			return retry_after.min(self.max_delay);
+		}
+		let exponential = self
+			.base_delay
+			.saturating_mul(2u32.saturating_pow(attempt))
+			.min(self.max_delay);
+		// "Equal jitter": wait at least half of the exponential delay, and a random amount of the rest.
+		let half = exponential / 2;
+		let jitter = rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+		half + Duration::from_millis(jitter)
+	}
+
+	/// Reads the `Retry-After` header, which is either a number of seconds or an http date.
+	pub fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+		let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+		if let Ok(seconds) = value.trim().parse::<u64>() {
+			return Some(Duration::from_secs(seconds));
+		}
+		let date = httpdate::parse_http_date(value).ok()?;
+		Some(
+			date.duration_since(SystemTime::now())
+				.unwrap_or(Duration::ZERO),
+		)
+	}
+}
@@ -0,0 +1,330 @@
+use super::{
+	creature_list::{CreatureListing, CreatureListingPage, PageIter},
+	WebpageProvider,
+};
+use crate::{selectors::Selectors, utility::Node};
+use std::{path::PathBuf, sync::Arc};
+
+/// A selector the scraper relies on, and the selectors relative to the elements it matches.
+///
+/// When checked against a sample of pages, each selector records how many of the pages it was checked on
+/// (those where its parent matched) it matched on, so selectors which no longer match the site's markup
+/// can be found before a full crawl fails on them.
+#[derive(Debug)]
+pub struct SelectorCheck {
+	field: &'static str,
+	selector: &'static Node,
+	/// Optional selectors only match on some pages (e.g. the hit dice of creatures which have them),
+	/// so they are only broken if they match none of the sampled pages.
+	optional: bool,
+	children: Vec<SelectorCheck>,
+	checked: usize,
+	missing_on: Vec<String>,
+}
+impl SelectorCheck {
+	fn new(field: &'static str, selector: &'static Node) -> Self {
+		Self {
+			field,
+			selector,
+			optional: false,
+			children: Vec::new(),
+			checked: 0,
+			missing_on: Vec::new(),
+		}
+	}
+
+	fn optional(mut self) -> Self {
+		self.optional = true;
+		self
+	}
+
+	fn with_children(mut self, children: impl IntoIterator<Item = SelectorCheck>) -> Self {
+		self.children.extend(children);
+		self
+	}
+
+	/// The selectors used to parse a page of the monster listing, from the root of the page.
+	pub fn listing_page() -> Vec<Self> {
+		let selectors = &Selectors::get().listing;
+		vec![
+			Self::new("pagination", &selectors.pagination).with_children([Self::new(
+				"pagination items",
+				&selectors.pagination_item,
+			)
+			.with_children([Self::new("last page", &selectors.pagination_link)])]),
+			Self::new("page content", &selectors.page_content).with_children([Self::new(
+				"monster listing",
+				&selectors.listing,
+			)
+			.with_children([Self::new("monster rows", &selectors.row).with_children([
+				Self::new("name and source", &selectors.title).with_children([
+					Self::new("name", &selectors.name_link),
+					Self::new("source book", &selectors.source_book),
+				]),
+				Self::new("challenge rating", &selectors.challenge_rating).optional(),
+				Self::new("type", &selectors.kind),
+				Self::new("size", &selectors.size),
+			])])]),
+		]
+	}
+
+	/// The selectors used to parse a creature's page, from the root of the page.
+	pub fn creature_page() -> Vec<Self> {
+		let selectors = &Selectors::get().creature;
+		let attributes = Self::new("attributes", &selectors.attributes).with_children([Self::new(
+			"attribute",
+			&selectors.attribute,
+		)
+		.with_children([
+			Self::new("attribute label", &selectors.attribute_label),
+			Self::new("armor class", &selectors.attribute_value).with_children([
+				Self::new("armor class value", &selectors.attribute_data_value),
+				Self::new("armor class source", &selectors.attribute_data_extra).optional(),
+			]),
+			Self::new("hit points and speed", &selectors.attribute_data).with_children([
+				Self::new(
+					"hit points and speed value",
+					&selectors.attribute_data_value,
+				),
+				Self::new("hit dice", &selectors.attribute_data_extra).optional(),
+			]),
+		])]);
+		let stats = Self::new("ability scores", &selectors.stats).with_children([Self::new(
+			"ability",
+			&selectors.ability,
+		)
+		.with_children([
+			Self::new("ability heading", &selectors.ability_heading),
+			Self::new("ability score", &selectors.ability_score),
+			Self::new("ability modifier", &selectors.ability_modifier),
+		])]);
+		let tidbits = Self::new("tidbits", &selectors.tidbits).with_children([Self::new(
+			"tidbit",
+			&selectors.tidbit,
+		)
+		.with_children([
+			Self::new("tidbit label", &selectors.tidbit_label),
+			Self::new("tidbit data", &selectors.tidbit_data),
+		])]);
+		let description_blocks = Self::new("description blocks", &selectors.description_block)
+			.with_children([
+				Self::new(
+					"description block heading",
+					&selectors.description_block_heading,
+				)
+				.optional(),
+				Self::new(
+					"description block content",
+					&selectors.description_block_content,
+				),
+			]);
+		vec![
+			Self::new("page content", &selectors.page_content).with_children([Self::new(
+				"creature details",
+				&selectors.details,
+			)
+			.with_children([
+				Self::new("stat block", &selectors.stat_block).with_children([
+					Self::new("stat block header", &selectors.header).with_children([
+						Self::new("creature name", &selectors.name_link),
+						Self::new("size, type, and alignment", &selectors.meta),
+					]),
+					attributes,
+					stats,
+					tidbits,
+					description_blocks,
+				]),
+			])]),
+		]
+	}
+
+	/// Checks the selector (and its children) against the elements matched by its parent on a page.
+	fn check(&mut self, url: &str, parents: &[scraper::ElementRef<'_>]) {
+		if parents.is_empty() {
+			return;
+		}
+		self.checked += 1;
+		let matches = parents
+			.iter()
+			.flat_map(|parent| self.selector.get_iter(parent))
+			.collect::<Vec<_>>();
+		if matches.is_empty() {
+			self.missing_on.push(url.to_owned());
+		}
+		for child in self.children.iter_mut() {
+			child.check(url, &matches);
+		}
+	}
+
+	/// Checks each of the selectors against a whole page.
+	pub fn check_page(checks: &mut [Self], url: &str, html: &str) {
+		let document = scraper::Html::parse_document(html);
+		let root = [document.root_element()];
+		for check in checks.iter_mut() {
+			check.check(url, &root);
+		}
+	}
+
+	/// True if the selector did not match a page it should have.
+	pub fn is_broken(&self) -> bool {
+		match self.optional {
+			true => self.checked > 0 && self.missing_on.len() == self.checked,
+			false => !self.missing_on.is_empty(),
+		}
+	}
+
+	/// Returns each broken selector, along with the fields of the selectors it is within.
+	pub fn broken(checks: &[Self]) -> Vec<(Vec<&'static str>, &Self)> {
+		let mut broken = Vec::new();
+		for check in checks.iter() {
+			check.collect_broken(&mut Vec::new(), &mut broken);
+		}
+		broken
+	}
+
+	fn collect_broken<'a>(
+		&'a self,
+		path: &mut Vec<&'static str>,
+		broken: &mut Vec<(Vec<&'static str>, &'a Self)>,
+	) {
+		path.push(self.field);
+		if self.is_broken() {
+			broken.push((path.clone(), self));
+		}
+		for child in self.children.iter() {
+			child.collect_broken(path, broken);
+		}
+		path.pop();
+	}
+
+	pub fn field(&self) -> &'static str {
+		self.field
+	}
+
+	pub fn selector(&self) -> &'static Node {
+		self.selector
+	}
+
+	/// The number of sampled pages the selector was checked against.
+	pub fn checked(&self) -> usize {
+		self.checked
+	}
+
+	/// The urls of the pages the selector did not match.
+	pub fn missing_on(&self) -> &Vec<String> {
+		&self.missing_on
+	}
+}
+impl std::fmt::Display for SelectorCheck {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{:?} matched {} of {} pages",
+			self.selector.as_str(),
+			self.checked - self.missing_on.len(),
+			self.checked
+		)?;
+		if let Some(url) = self.missing_on.first() {
+			write!(f, " (e.g. not {url:?})")?;
+		}
+		Ok(())
+	}
+}
+
+/// The selectors checked against a sample of listing and creature pages.
+pub struct SelectorReport {
+	listing_pages: usize,
+	listing_checks: Vec<SelectorCheck>,
+	creature_pages: usize,
+	creature_checks: Vec<SelectorCheck>,
+}
+impl SelectorReport {
+	/// Fetches the first page of the monster listing, and the pages of (up to) `sample` creatures listed on it
+	/// along with each of `creature_urls` (e.g. `/monsters/16921-guard`), and checks the selectors against them.
+	pub async fn sample(
+		provider: &Arc<WebpageProvider>,
+		sample: usize,
+		creature_urls: Vec<PathBuf>,
+	) -> anyhow::Result<Self> {
+		let mut report = Self {
+			listing_pages: 0,
+			listing_checks: SelectorCheck::listing_page(),
+			creature_pages: 0,
+			creature_checks: SelectorCheck::creature_page(),
+		};
+
+		let mut urls = creature_urls;
+		if let Some(listing_url) = PageIter::with_range(provider, 0..1)?.next() {
+			let url = reqwest::Url::parse(&listing_url)?;
+			let body = provider.fetch(url.clone())?.await?.text().await?;
+			SelectorCheck::check_page(&mut report.listing_checks, url.as_str(), &body);
+			report.listing_pages += 1;
+
+			// Sample the creatures from whichever rows of the listing can still be parsed.
+			let page = CreatureListingPage::new(&url, &body);
+			let rows = page.list().map(|list| list.children());
+			match rows {
+				Ok(rows) => {
+					let listed = rows
+						.into_iter()
+						.filter_map(|row| CreatureListing::try_from(row).ok())
+						.map(|listing| listing.url().clone());
+					urls.extend(listed.take(sample));
+				}
+				Err(err) => log::warn!("Could not find creatures to sample in the listing: {err}"),
+			}
+		}
+
+		for creature_url in urls.iter() {
+			let url = provider.url(&creature_url.to_string_lossy())?;
+			let body = match provider.fetch(url.clone())?.await {
+				Ok(response) => response.text().await?,
+				Err(err) => {
+					log::error!("Could not fetch {:?} to check it: {err:?}", url.as_str());
+					continue;
+				}
+			};
+			SelectorCheck::check_page(&mut report.creature_checks, url.as_str(), &body);
+			report.creature_pages += 1;
+		}
+
+		Ok(report)
+	}
+
+	pub fn listing_checks(&self) -> &Vec<SelectorCheck> {
+		&self.listing_checks
+	}
+
+	pub fn creature_checks(&self) -> &Vec<SelectorCheck> {
+		&self.creature_checks
+	}
+
+	/// The number of selectors which no longer match.
+	pub fn broken_count(&self) -> usize {
+		SelectorCheck::broken(&self.listing_checks).len()
+			+ SelectorCheck::broken(&self.creature_checks).len()
+	}
+}
+impl std::fmt::Display for SelectorReport {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let sections = [
+			("listing", self.listing_pages, &self.listing_checks),
+			("creature", self.creature_pages, &self.creature_checks),
+		];
+		for (idx, (kind, pages, checks)) in sections.into_iter().enumerate() {
+			if idx > 0 {
+				writeln!(f)?;
+			}
+			let broken = SelectorCheck::broken(checks);
+			write!(
+				f,
+				"Checked {kind} selectors against {pages} pages: {} broken",
+				broken.len()
+			)?;
+			for (path, check) in broken {
+				write!(f, "\n- {}: {check}", path.join(" > "))?;
+			}
+		}
+		Ok(())
+	}
+}
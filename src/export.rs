@@ -0,0 +1,120 @@
+use crate::{
+	creature::Creature,
+	database::{entity, Database},
+};
+use anyhow::Context;
+use sea_orm::{EntityTrait, QueryOrder};
+use std::path::{Path, PathBuf};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+	Json,
+}
+
+/// Writes every creature in the database to the output directory,
+/// along with a json schema (`schema.json`) describing the exported files.
+///
+/// If `split` is true, each creature is written to its own file in `<output>/creatures/`,
+/// otherwise all creatures are written as a single array to `<output>/creatures.json`.
+pub async fn export(
+	database: &Database,
+	format: ExportFormat,
+	output: &Path,
+	split: bool,
+) -> anyhow::Result<usize> {
+	match format {
+		ExportFormat::Json => export_json(database, output, split).await,
+	}
+}
+
+async fn export_json(database: &Database, output: &Path, split: bool) -> anyhow::Result<usize> {
+	let rows = entity::creature::Entity::find()
+		.order_by_asc(entity::creature::Column::Name)
+		.all(database.connection())
+		.await?;
+
+	let mut creatures = Vec::with_capacity(rows.len());
+	let mut with_warnings = 0;
+	let mut skipped = 0;
+	for row in rows.into_iter() {
+		let Some(data) = row.data else {
+			log::warn!(
+				"Skipping creature {:?}, it was saved before full data was stored. Fetch it again to export it.",
+				row.name
+			);
+			continue;
+		};
+		// Data saved with an older shape would not match the schema.
+		if row.data_version != Some(Creature::DATA_VERSION) {
+			log::warn!(
+				"Skipping creature {:?}, it was saved by an older version of dndscrape. Fetch it again to export it.",
+				row.name
+			);
+			skipped += 1;
+			continue;
+		}
+		let value = serde_json::from_str::<serde_json::Value>(&data)
+			.with_context(|| format!("reading saved data for creature {:?}", row.url))?;
+		if value["warnings"]
+			.as_array()
+			.is_some_and(|warnings| !warnings.is_empty())
+		{
+			with_warnings += 1;
+		}
+		creatures.push((row.url, value));
+	}
+	if skipped > 0 {
+		log::warn!("Skipped {skipped} creatures saved by an older version, which `dndscrape fetch` will fetch again");
+	}
+	if with_warnings > 0 {
+		log::warn!("{with_warnings} creatures have printed values which disagree with the rest of their stat block, listed in their `warnings`");
+	}
+
+	tokio::fs::create_dir_all(output).await?;
+	let schema = match split {
+		true => schemars::schema_for!(Creature),
+		false => schemars::schema_for!(Vec<Creature>),
+	};
+	write_json(&output.join("schema.json"), &schema).await?;
+
+	let count = creatures.len();
+	match split {
+		true => {
+			let directory = output.join("creatures");
+			tokio::fs::create_dir_all(&directory).await?;
+			for (url, value) in creatures.into_iter() {
+				write_json(&directory.join(file_name(&url)), &value).await?;
+			}
+		}
+		false => {
+			let values = creatures
+				.into_iter()
+				.map(|(_, value)| value)
+				.collect::<Vec<_>>();
+			write_json(&output.join("creatures.json"), &values).await?;
+		}
+	}
+	Ok(count)
+}
+
+/// The name of the file for a creature, based on the last segment of its url (`/monsters/16921-guard` => `16921-guard.json`).
+fn file_name(url: &str) -> PathBuf {
+	let stem = url
+		.trim_end_matches('/')
+		.rsplit('/')
+		.next()
+		.filter(|segment| !segment.is_empty())
+		.unwrap_or("creature");
+	PathBuf::from(format!("{stem}.json"))
+}
+
+async fn write_json<T>(path: &Path, value: &T) -> anyhow::Result<()>
+where
+	T: serde::Serialize,
+{
+	let content = serde_json::to_string_pretty(value)?;
+	tokio::fs::write(path, content)
+		.await
+		.with_context(|| format!("writing {path:?}"))?;
+	Ok(())
+}
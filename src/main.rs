@@ -1,13 +1,36 @@
 use clap::Parser;
-use std::sync::Arc;
+use std::{
+	collections::{HashMap, HashSet},
+	path::PathBuf,
+	sync::Arc,
+};
 
-use creature::Creature;
-use dndbeyond::{creature_list::CreatureListing, WebpageProvider};
+use creature::{challenge_rating::ChallengeRating, diagnostic::ParseMode};
+use database::Database;
+use dndbeyond::{
+	cache::{CacheMode, ResponseCache},
+	creature_list::CreatureListing,
+	listing_filter::ListingFilter,
+	rate_limit::RateLimiter,
+	retry::RetryPolicy,
+	selector_check::SelectorReport,
+	WebpageProvider,
+};
+use export::ExportFormat;
+use report::{Failure, ListingChange, RunReport};
+use selectors::Selectors;
 
 pub mod creature;
+pub mod database;
 pub mod dndbeyond;
+pub mod export;
+pub mod report;
+pub mod selectors;
 pub mod utility;
 
+#[cfg(test)]
+mod tests;
+
 fn main() -> anyhow::Result<()> {
 	log_base::init(
 		std::env!("CARGO_PKG_NAME"),
@@ -28,7 +51,219 @@ fn main() -> anyhow::Result<()> {
 
 #[derive(Parser, Debug)]
 enum Cli {
-	Fetch,
+	/// Scrapes creatures from D&D Beyond, saving them to a local database.
+	Fetch {
+		/// The sqlite database file to save creatures to.
+		#[arg(long, default_value = "creatures.db")]
+		database: PathBuf,
+		/// Continue the last crawl, skipping the listing pages and creatures it finished
+		/// and retrying the ones which failed.
+		#[arg(long)]
+		resume: bool,
+		/// Fetch every creature, instead of only those which are new or whose listing has changed.
+		#[arg(long)]
+		full: bool,
+		/// Where to write the report of the run, listing every creature which failed and why.
+		#[arg(long, default_value = "target/fetch-report.json")]
+		report: PathBuf,
+		/// How to handle fields of a creature's stat block which cannot be parsed.
+		#[arg(long, value_enum, default_value_t = ParseMode::Strict)]
+		parse_mode: ParseMode,
+		#[command(flatten)]
+		filter: ListingFilterArgs,
+		#[command(flatten)]
+		selectors: SelectorArgs,
+		#[command(flatten)]
+		cache: CacheArgs,
+		#[command(flatten)]
+		retry: RetryArgs,
+		#[command(flatten)]
+		rate_limit: RateLimitArgs,
+	},
+	/// Writes the creatures in the local database to files, along with a schema describing them.
+	Export {
+		/// The sqlite database file to read creatures from.
+		#[arg(long, default_value = "creatures.db")]
+		database: PathBuf,
+		#[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+		format: ExportFormat,
+		/// The directory to write the exported files to.
+		#[arg(long, default_value = "export")]
+		output: PathBuf,
+		/// Write each creature to its own file, instead of a single array of all creatures.
+		#[arg(long)]
+		split: bool,
+	},
+	/// Parses creature pages saved by a previous fetch, saving them to the local database.
+	/// Each page (`<name>.html`) needs the listing saved next to it (`<name>.listing.json`).
+	Parse {
+		/// The directory of saved creature pages.
+		#[arg(default_value = "target/monsters")]
+		directory: PathBuf,
+		/// The sqlite database file to save creatures to.
+		#[arg(long, default_value = "creatures.db")]
+		database: PathBuf,
+		/// Where to write the report of the run, listing every creature which failed and why.
+		#[arg(long, default_value = "target/parse-report.json")]
+		report: PathBuf,
+		/// How to handle fields of a creature's stat block which cannot be parsed.
+		#[arg(long, value_enum, default_value_t = ParseMode::Strict)]
+		parse_mode: ParseMode,
+		#[command(flatten)]
+		selectors: SelectorArgs,
+	},
+	/// Fetches the first listing page and a sample of creature pages, and reports which of the selectors
+	/// used to parse them no longer match, so changes to the site's markup are found before a full crawl.
+	CheckSelectors {
+		/// How many of the creatures on the first listing page to check.
+		#[arg(long, default_value_t = 3)]
+		sample: usize,
+		/// Also check the page of this creature (e.g. "/monsters/16921-guard"). Can be given more than once.
+		#[arg(long = "creature", value_name = "URL")]
+		creature_urls: Vec<PathBuf>,
+		#[command(flatten)]
+		selectors: SelectorArgs,
+		#[command(flatten)]
+		cache: CacheArgs,
+		#[command(flatten)]
+		retry: RetryArgs,
+		#[command(flatten)]
+		rate_limit: RateLimitArgs,
+	},
+}
+
+#[derive(clap::Args, Debug)]
+struct CacheArgs {
+	/// The directory to cache fetched webpages in.
+	#[arg(long, default_value = "target/cache")]
+	cache_dir: PathBuf,
+	/// How long (in seconds) a cached webpage is used before asking the site if it has changed.
+	#[arg(long, default_value_t = 60 * 60 * 24)]
+	cache_ttl: u64,
+	/// Only use cached webpages, never fetching from the site. Pages which are not cached will fail.
+	#[arg(long, conflicts_with = "no_cache")]
+	cache_only: bool,
+	/// Don't read or write the webpage cache.
+	#[arg(long)]
+	no_cache: bool,
+}
+impl CacheArgs {
+	fn build(&self) -> Option<ResponseCache> {
+		if self.no_cache {
+			return None;
+		}
+		let mode = match self.cache_only {
+			true => CacheMode::Offline,
+			false => CacheMode::Normal,
+		};
+		Some(ResponseCache::new(
+			self.cache_dir.clone(),
+			std::time::Duration::from_secs(self.cache_ttl),
+			mode,
+		))
+	}
+}
+
+#[derive(clap::Args, Debug)]
+struct RetryArgs {
+	/// How many times a failed request is retried before giving up.
+	#[arg(long, default_value_t = 3)]
+	retries: u32,
+	/// The delay (in milliseconds) before the first retry, which doubles for each retry after.
+	#[arg(long, default_value_t = 500)]
+	retry_delay: u64,
+	/// The longest delay (in milliseconds) between retries, unless the site asks for a longer one.
+	#[arg(long, default_value_t = 30_000)]
+	retry_max_delay: u64,
+}
+impl RetryArgs {
+	fn build(&self) -> RetryPolicy {
+		RetryPolicy::new(
+			self.retries,
+			std::time::Duration::from_millis(self.retry_delay),
+			std::time::Duration::from_millis(self.retry_max_delay),
+		)
+	}
+}
+
+#[derive(clap::Args, Debug)]
+struct ListingFilterArgs {
+	/// Only fetch creatures whose name matches the glob pattern (e.g. "Young * Dragon"), ignoring case.
+	/// Can be given more than once to fetch creatures matching any of the patterns.
+	#[arg(long = "name", value_name = "GLOB")]
+	names: Vec<String>,
+	/// Only fetch creatures whose name matches the regular expression.
+	/// Can be given more than once, and combined with `--name`.
+	#[arg(long = "name-regex", value_name = "REGEX")]
+	name_regexes: Vec<String>,
+	/// Only fetch creatures with at least this challenge rating (e.g. "1/4" or "5").
+	#[arg(long)]
+	min_cr: Option<ChallengeRating>,
+	/// Only fetch creatures with at most this challenge rating.
+	#[arg(long)]
+	max_cr: Option<ChallengeRating>,
+	/// Only fetch creatures of this type (e.g. "Humanoid"). Can be given more than once.
+	#[arg(long = "type", value_name = "TYPE")]
+	kinds: Vec<String>,
+	/// Only fetch creatures of this size (e.g. "Medium"). Can be given more than once.
+	#[arg(long = "size", value_name = "SIZE")]
+	sizes: Vec<String>,
+	/// Only fetch creatures from this source book (e.g. "Monster Manual"). Can be given more than once.
+	#[arg(long = "source", value_name = "SOURCE")]
+	source_books: Vec<String>,
+}
+impl ListingFilterArgs {
+	fn build(&self) -> anyhow::Result<ListingFilter> {
+		let mut filter =
+			ListingFilter::default().with_challenge_rating(self.min_cr, self.max_cr)?;
+		for pattern in self.names.iter() {
+			filter = filter.with_name_glob(pattern)?;
+		}
+		for pattern in self.name_regexes.iter() {
+			filter = filter.with_name_regex(pattern)?;
+		}
+		for kind in self.kinds.iter() {
+			filter = filter.with_kind(kind);
+		}
+		for size in self.sizes.iter() {
+			filter = filter.with_size(size);
+		}
+		for source_book in self.source_books.iter() {
+			filter = filter.with_source_book(source_book);
+		}
+		Ok(filter)
+	}
+}
+
+#[derive(clap::Args, Debug)]
+struct RateLimitArgs {
+	/// The most requests per second sent to the site, across all workers. Use 0 for no limit.
+	#[arg(long, default_value_t = 2.0)]
+	rate_limit: f64,
+	/// How many requests can be sent at once before the rate limit applies.
+	#[arg(long, default_value_t = 5)]
+	burst: u32,
+}
+impl RateLimitArgs {
+	fn build(&self) -> Option<RateLimiter> {
+		match self.rate_limit > 0.0 {
+			true => Some(RateLimiter::new(self.rate_limit, self.burst)),
+			false => None,
+		}
+	}
+}
+
+#[derive(clap::Args, Debug)]
+struct SelectorArgs {
+	/// A toml file of css selectors which replace the built-in ones (see `src/selectors.toml`),
+	/// to work around changes to the site's markup.
+	#[arg(long, env = "DNDSCRAPE_SELECTORS")]
+	selectors: Option<PathBuf>,
+}
+impl SelectorArgs {
+	async fn build(&self) -> anyhow::Result<Selectors> {
+		Selectors::load(self.selectors.as_deref()).await
+	}
 }
 
 async fn run() -> anyhow::Result<()> {
@@ -38,108 +273,309 @@ async fn run() -> anyhow::Result<()> {
 impl Cli {
 	async fn run(&self) -> anyhow::Result<()> {
 		match self {
-			Self::Fetch => {
-				tokio::fs::create_dir_all("html").await?;
+			Self::Fetch {
+				database,
+				resume,
+				full,
+				report: report_path,
+				parse_mode,
+				filter,
+				selectors,
+				cache,
+				retry,
+				rate_limit,
+			} => {
+				selectors.build().await?.install()?;
+				let filter = filter.build()?;
+				let database = Database::open(database).await?;
 
-				let worker_tasks;
-				{
-					let provider = Arc::new(WebpageProvider::new().await?);
-					// The number of worker tasks spawned here is the number of
-					// webpage fetch/get requests that can be processed in parallel.
-					worker_tasks = provider.spawn_workers(10);
-
-					let (pages, errors) =
-						CreatureListing::fetch_pages(provider, Some(0..1)).await?;
-					for err in errors.into_iter() {
-						log::error!("{err:?}");
+				let mut provider = WebpageProvider::new()
+					.await?
+					.with_retry_policy(retry.build());
+				if let Some(cache) = cache.build() {
+					provider = provider.with_cache(cache);
+				}
+				if let Some(rate_limiter) = rate_limit.build() {
+					provider = provider.with_rate_limit(rate_limiter);
+				}
+				let report =
+					crawl(provider, &database, filter, *resume, *full, *parse_mode).await?;
+
+				report.write_json(report_path).await?;
+				log::info!("{report}");
+				log::info!("Wrote the report of this run to {report_path:?}");
+				if report.failed() > 0 {
+					log::info!("Run again with `--resume` to retry the failed creatures");
+				}
+
+				Ok(())
+			}
+			Self::Export {
+				database,
+				format,
+				output,
+				split,
+			} => {
+				let database = Database::open(database).await?;
+				let count = export::export(&database, *format, output, *split).await?;
+				log::info!("Exported {count} creatures to {output:?}");
+				Ok(())
+			}
+			Self::Parse {
+				directory,
+				database,
+				report: report_path,
+				parse_mode,
+				selectors,
+			} => {
+				selectors.build().await?.install()?;
+				let database = Database::open(database).await?;
+				let stored = database.stored_listings().await?;
+
+				let mut html_paths = Vec::new();
+				let mut entries = tokio::fs::read_dir(directory).await?;
+				while let Some(entry) = entries.next_entry().await? {
+					let path = entry.path();
+					if path.extension().and_then(|ext| ext.to_str()) == Some("html") {
+						html_paths.push(path);
 					}
+				}
+				html_paths.sort();
 
-					// Now parse each page and find all of the creature listings
-					for (_url, body) in pages.into_iter() {
-						log::debug!("{body:?}");
-						let page = dndbeyond::creature_list::CreatureListingPage::from(&body);
-						let list_elements = page.list().children();
-						for element in list_elements.into_iter() {
-							let url_path = element.title_block().name_link().url();
-							log::debug!("{url_path:?}");
+				let mut report = RunReport::default();
+				for html_path in html_paths.iter() {
+					match CreatureListing::parse_saved(html_path, *parse_mode).await {
+						Ok(creature) => {
+							database.upsert_creature(&creature).await?;
+							report.record(ListingChange::compare(creature.listing(), &stored));
+						}
+						Err(err) => {
+							log::error!("{err:?}");
+							report.record_failure(Failure::new(
+								None,
+								&err,
+								Some(html_path.clone()),
+							));
 						}
 					}
-				};
-
-				// Technically, if all the work has finished, then these tasks could be dropped without caring
-				// if the channels still exist (because they are garunteed to be empty).
-				// For the sake of consistency, we stitch the worker tasks back into main thread.
-				// If this hangs, its because the sender channel for the requests still exists (it lives in the WebpageProvider).
+				}
+				report.write_json(report_path).await?;
+				log::info!("Parsed creatures from {directory:?}. {report}");
+				log::info!("Wrote the report of this run to {report_path:?}");
+				Ok(())
+			}
+			Self::CheckSelectors {
+				sample,
+				creature_urls,
+				selectors,
+				cache,
+				retry,
+				rate_limit,
+			} => {
+				selectors.build().await?.install()?;
+				let worker_tasks;
+				let report;
+				{
+					let mut provider = WebpageProvider::new()
+						.await?
+						.with_retry_policy(retry.build());
+					if let Some(cache) = cache.build() {
+						provider = provider.with_cache(cache);
+					}
+					if let Some(rate_limiter) = rate_limit.build() {
+						provider = provider.with_rate_limit(rate_limiter);
+					}
+					let provider = Arc::new(provider);
+					worker_tasks = provider.spawn_workers(2);
+					report =
+						SelectorReport::sample(&provider, *sample, creature_urls.clone()).await?;
+				}
 				futures::future::join_all(worker_tasks).await;
 
-				Ok(())
+				log::info!("{report}");
+				match report.broken_count() {
+					0 => Ok(()),
+					broken => Err(anyhow::anyhow!("{broken} selectors no longer match")),
+				}
 			}
 		}
 	}
 }
 
-async fn run_old() -> anyhow::Result<()> {
-	let worker_tasks;
-	let creatures;
-	{
-		let provider = Arc::new(WebpageProvider::new().await?);
-		// The number of worker tasks spawned here is the number of
-		// webpage fetch/get requests that can be processed in parallel.
-		worker_tasks = provider.spawn_workers(10);
+/// Crawls the monster listing, fetching the page of each creature in it which matches the filter
+/// and saving the creature to the database. Returns the report of the run.
+///
+/// Continues the last crawl recorded in the database's checkpoint if `resume` is true,
+/// and fetches every creature (instead of only those which are new or changed) if `full` is true.
+async fn crawl(
+	provider: WebpageProvider,
+	database: &Database,
+	filter: ListingFilter,
+	resume: bool,
+	full: bool,
+	parse_mode: ParseMode,
+) -> anyhow::Result<RunReport> {
+	let checkpoint = database.checkpoint();
+	// The creatures saved by previous crawls, to compare the new listings against.
+	let stored = Arc::new(database.stored_listings().await?);
 
-		let (send_creature, recv_creature) = async_channel::unbounded();
+	let provider = Arc::new(provider);
+	// The number of worker tasks spawned here is the number of
+	// webpage fetch/get requests that can be processed in parallel.
+	let worker_tasks = provider.spawn_workers(10);
 
-		CreatureListing::fetch_all(provider.clone(), send_creature, Some(0..8)).await?;
-		let creature_tasks = fetch_creature_pages(provider, recv_creature);
-
-		creatures = creature_tasks.await??;
-	}
+	// The provider is moved in (and dropped when the crawl finishes), closing the channel to the workers.
+	let result = crawl_listings(
+		provider,
+		database,
+		stored.clone(),
+		filter,
+		resume,
+		full,
+		parse_mode,
+	)
+	.await;
 
 	// Technically, if all the work has finished, then these tasks could be dropped without caring
 	// if the channels still exist (because they are garunteed to be empty).
-	// For the sake of consistency, we stitch the worker tasks back into main thread.
+	// For the sake of consistency, we stitch the worker tasks back into main thread, even if the crawl failed.
 	// If this hangs, its because the sender channel for the requests still exists (it lives in the WebpageProvider).
 	futures::future::join_all(worker_tasks).await;
+	let mut report = result?;
 
-	log::debug!("Finished collecting {} creatures", creatures.len());
-	//log::debug!("{creatures:?}");
+	// Creatures can only be known to be removed if every listing page was seen.
+	if checkpoint.has_failed_pages().await? {
+		log::warn!("Some listing pages failed, so removed creatures were not counted");
+	} else {
+		let listed = checkpoint.listed_urls().await?;
+		for url in stored.keys().filter(|url| !listed.contains(*url)) {
+			log::info!("{url:?} is no longer listed");
+			report.record_removed();
+		}
+	}
+
+	Ok(report)
+}
+
+/// Sends each creature in the monster listing (or the unfinished creatures of the last crawl, if `resume` is true)
+/// to [`fetch_creature_pages`], and returns its report once every creature has been fetched.
+///
+/// The creature pages are always finished before returning, even if the listing fails part way through.
+async fn crawl_listings(
+	provider: Arc<WebpageProvider>,
+	database: &Database,
+	stored: Arc<HashMap<PathBuf, Option<CreatureListing>>>,
+	filter: ListingFilter,
+	resume: bool,
+	full: bool,
+	parse_mode: ParseMode,
+) -> anyhow::Result<RunReport> {
+	let checkpoint = database.checkpoint();
+	let (send_creature, recv_creature) = async_channel::unbounded();
 
-	Ok(())
+	if resume {
+		let unfinished = checkpoint.unfinished_creatures().await?;
+		log::info!(
+			"Resuming crawl with {} unfinished creatures",
+			unfinished.len()
+		);
+		for listing in unfinished.into_iter() {
+			let _ = send_creature.try_send(listing);
+		}
+	} else {
+		checkpoint.clear().await?;
+	}
+
+	let creature_tasks = fetch_creature_pages(
+		provider.clone(),
+		recv_creature,
+		database.clone(),
+		stored,
+		filter,
+		full,
+		parse_mode,
+	);
+	// The sender is dropped once the listing is done (or fails), which lets the creature tasks finish.
+	let listed = CreatureListing::fetch_all(provider, send_creature, None, Some(checkpoint)).await;
+	let report = creature_tasks.await;
+	listed?;
+	report?
 }
 
+/// Fetches the page of each creature received from the channel which matches the filter,
+/// saving each creature to the database and recording it in the database's checkpoint.
+/// Creatures whose listing has not changed since they were stored are skipped, unless `full` is true.
 fn fetch_creature_pages(
 	provider: Arc<WebpageProvider>,
 	channel: async_channel::Receiver<CreatureListing>,
-) -> tokio::task::JoinHandle<anyhow::Result<Vec<Creature>>> {
+	database: Database,
+	stored: Arc<HashMap<PathBuf, Option<CreatureListing>>>,
+	filter: ListingFilter,
+	full: bool,
+	parse_mode: ParseMode,
+) -> tokio::task::JoinHandle<anyhow::Result<RunReport>> {
+	let checkpoint = database.checkpoint();
 	let creature_collector = tokio::task::spawn(async move {
+		let mut report = RunReport::default();
 		let mut parsing_tasks = Vec::new();
+		let mut seen_urls = HashSet::new();
 		while let Ok(listing) = channel.recv().await {
 			let provider = provider.clone();
+			let database = database.clone();
+			let checkpoint = checkpoint.clone();
 
-			match listing.name().as_str() {
-				"Almiraj" | "Avatar of Death" | "Chwinga Astronaut" | "Guard" | "Frog" => {}
-				_ => continue,
+			if !filter.matches(&listing) {
+				checkpoint.skip_creature(listing.url()).await?;
+				continue;
+			}
+			if !seen_urls.insert(listing.url().clone()) {
+				continue;
+			}
+
+			let change = ListingChange::compare(&listing, &stored);
+			if matches!(change, ListingChange::Unchanged) && !full {
+				log::debug!("Skipping {:?}, which has not changed", listing.name());
+				checkpoint.complete_creature(listing.url()).await?;
+				report.record(change);
+				continue;
 			}
 
 			parsing_tasks.push(tokio::task::spawn(async move {
-				match listing.fetch_full(&provider).await {
-					Ok(creature) => Some(creature),
+				let url = listing.url().clone();
+				let result = async {
+					let creature = listing.clone().fetch_full(&provider, parse_mode).await?;
+					database.upsert_creature(&creature).await?;
+					Ok(()) as anyhow::Result<()>
+				};
+				let outcome = match result.await {
+					Ok(()) => {
+						checkpoint.complete_creature(&url).await?;
+						Ok(change)
+					}
 					Err(err) => {
 						log::error!("{err:?}");
-						None
+						checkpoint.fail_creature(&url, &err).await?;
+						let html_path = listing.saved_page_path(provider.page_dir()).ok();
+						Err(Failure::new(Some(&listing), &err, html_path))
 					}
-				}
+				};
+				Ok(outcome) as anyhow::Result<Result<ListingChange, Failure>>
 			}));
 		}
-		parsing_tasks
+		Ok((parsing_tasks, report)) as anyhow::Result<(Vec<_>, RunReport)>
 	});
 	tokio::task::spawn(async move {
-		let creature_tasks = creature_collector.await?;
-		let creatures = futures::future::join_all(creature_tasks).await;
-		let creatures = creatures
-			.into_iter()
-			.filter_map(|res| res.ok().flatten())
-			.collect::<Vec<_>>();
-		Ok(creatures) as anyhow::Result<Vec<Creature>>
+		let (creature_tasks, mut report) = creature_collector.await??;
+		for result in futures::future::join_all(creature_tasks).await {
+			match result? {
+				Ok(Ok(change)) => report.record(change),
+				Ok(Err(failure)) => report.record_failure(failure),
+				Err(err) => {
+					log::error!("Failed to record crawl progress: {err:?}");
+					report.record_failure(Failure::new(None, &err, None));
+				}
+			}
+		}
+		Ok(report) as anyhow::Result<RunReport>
 	})
 }
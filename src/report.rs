@@ -0,0 +1,152 @@
+use crate::{
+	creature::diagnostic::FieldError, dndbeyond::creature_list::CreatureListing,
+	utility::ParseError,
+};
+use anyhow::Context;
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+};
+
+/// How a listing compares to the creature saved by a previous crawl.
+#[derive(Debug, Clone, Copy)]
+pub enum ListingChange {
+	/// The creature has not been saved before.
+	Added,
+	/// The listing is different than the one the saved creature was fetched from (or that listing is unknown).
+	Changed,
+	Unchanged,
+}
+impl ListingChange {
+	pub fn compare(
+		listing: &CreatureListing,
+		stored: &HashMap<PathBuf, Option<CreatureListing>>,
+	) -> Self {
+		match stored.get(listing.url()) {
+			None => Self::Added,
+			Some(Some(previous)) if !listing.has_changed(previous) => Self::Unchanged,
+			Some(_) => Self::Changed,
+		}
+	}
+}
+
+/// The outcome of a run of the scraper: how many creatures were saved (by how they compare to the creatures
+/// saved by previous runs), and why each creature which failed did so.
+///
+/// Written as json for tooling, and logged as a human readable summary (see the [`Display`](std::fmt::Display) impl).
+#[derive(Debug, Default, serde::Serialize)]
+pub struct RunReport {
+	added: usize,
+	changed: usize,
+	removed: usize,
+	unchanged: usize,
+	failed: usize,
+	failures: Vec<Failure>,
+}
+
+/// A creature which could not be fetched, parsed, or saved.
+#[derive(Debug, serde::Serialize)]
+pub struct Failure {
+	/// The name of the creature, if its listing is known.
+	name: Option<String>,
+	/// The D&D Beyond url of the creature, if its listing is known.
+	url: Option<PathBuf>,
+	/// The error, followed by each of the errors which caused it.
+	chain: Vec<String>,
+	/// The field of the page which could not be parsed, if the failure was a [`FieldError`] or [`ParseError`].
+	field: Option<String>,
+	/// The saved html of the creature's page, if it was fetched.
+	html_path: Option<PathBuf>,
+}
+impl Failure {
+	pub fn new(
+		listing: Option<&CreatureListing>,
+		error: &anyhow::Error,
+		html_path: Option<PathBuf>,
+	) -> Self {
+		Self {
+			name: listing.map(|listing| listing.name().clone()),
+			url: listing.map(|listing| listing.url().clone()),
+			chain: error.chain().map(|cause| cause.to_string()).collect(),
+			field: Self::field(error),
+			html_path: html_path.filter(|path| path.exists()),
+		}
+	}
+
+	/// The field named by the error, preferring the field of the stat block (the same field
+	/// lenient parsing records a diagnostic for) over the element of the page it was parsed from.
+	fn field(error: &anyhow::Error) -> Option<String> {
+		let field = error
+			.chain()
+			.find_map(|cause| cause.downcast_ref::<FieldError>())
+			.map(|cause| cause.field().clone());
+		field.or_else(|| {
+			error
+				.chain()
+				.find_map(|cause| cause.downcast_ref::<ParseError>())
+				.map(|cause| cause.field().to_owned())
+		})
+	}
+}
+
+impl RunReport {
+	pub fn record(&mut self, change: ListingChange) {
+		match change {
+			ListingChange::Added => self.added += 1,
+			ListingChange::Changed => self.changed += 1,
+			ListingChange::Unchanged => self.unchanged += 1,
+		}
+	}
+
+	/// Counts a creature which was saved by a previous run, but is no longer listed.
+	pub fn record_removed(&mut self) {
+		self.removed += 1;
+	}
+
+	pub fn record_failure(&mut self, failure: Failure) {
+		self.failed += 1;
+		self.failures.push(failure);
+	}
+
+	pub fn failed(&self) -> usize {
+		self.failed
+	}
+
+	/// Writes the report as json to the path, creating its parent directories if needed.
+	pub async fn write_json(&self, path: &Path) -> anyhow::Result<()> {
+		if let Some(parent) = path.parent() {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+		tokio::fs::write(path, serde_json::to_string_pretty(self)?)
+			.await
+			.with_context(|| format!("writing report {path:?}"))?;
+		Ok(())
+	}
+}
+impl std::fmt::Display for RunReport {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"Added {} creatures, changed {}, removed {}, unchanged {}, failed {}",
+			self.added, self.changed, self.removed, self.unchanged, self.failed
+		)?;
+		for failure in self.failures.iter() {
+			let name = match (&failure.name, &failure.url, &failure.html_path) {
+				(Some(name), Some(url), _) => format!("{name} ({})", url.display()),
+				(_, _, Some(html_path)) => html_path.display().to_string(),
+				_ => "unknown creature".to_owned(),
+			};
+			write!(f, "\n- {name}")?;
+			if let Some(field) = &failure.field {
+				write!(f, " [{field}]")?;
+			}
+			if !failure.chain.is_empty() {
+				write!(f, ": {}", failure.chain.join(": "))?;
+			}
+			if let Some(html_path) = &failure.html_path {
+				write!(f, "\n  saved page: {}", html_path.display())?;
+			}
+		}
+		Ok(())
+	}
+}
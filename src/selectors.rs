@@ -0,0 +1,128 @@
+use crate::utility::Node;
+use anyhow::Context;
+use std::{path::Path, sync::OnceLock};
+
+/// The default selectors, which match the site's markup as of this release.
+static DEFAULTS: &str = include_str!("selectors.toml");
+static SELECTORS: OnceLock<Selectors> = OnceLock::new();
+
+/// The css selectors used to parse D&D Beyond pages, loaded from `selectors.toml`
+/// (embedded in the binary) and optionally overridden by a user supplied file.
+///
+/// The selectors in use are installed once at startup (see [`Selectors::install`]),
+/// and are read by the parsers with [`Selectors::get`].
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Selectors {
+	pub listing: ListingSelectors,
+	pub creature: CreatureSelectors,
+}
+
+/// The selectors for a page of the monster listing.
+/// Each is relative to the element of the section it is within (see `selectors.toml`).
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ListingSelectors {
+	pub pagination: Node,
+	pub pagination_item: Node,
+	pub pagination_link: Node,
+	pub page_content: Node,
+	pub listing: Node,
+	pub row: Node,
+	pub title: Node,
+	pub name_link: Node,
+	pub source_book: Node,
+	pub challenge_rating: Node,
+	pub kind: Node,
+	pub size: Node,
+}
+
+/// The selectors for a creature's page.
+/// Each is relative to the element of the section it is within (see `selectors.toml`).
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreatureSelectors {
+	pub page_content: Node,
+	pub details: Node,
+	pub stat_block: Node,
+	pub header: Node,
+	pub name_link: Node,
+	pub meta: Node,
+	pub attributes: Node,
+	pub attribute: Node,
+	pub attribute_label: Node,
+	pub attribute_value: Node,
+	pub attribute_data: Node,
+	pub attribute_data_value: Node,
+	pub attribute_data_extra: Node,
+	pub stats: Node,
+	pub ability: Node,
+	pub ability_heading: Node,
+	pub ability_score: Node,
+	pub ability_modifier: Node,
+	pub tidbits: Node,
+	pub tidbit: Node,
+	pub tidbit_label: Node,
+	pub tidbit_data: Node,
+	pub description_block: Node,
+	pub description_block_heading: Node,
+	pub description_block_content: Node,
+}
+
+impl Selectors {
+	/// The selectors embedded in the binary.
+	pub fn defaults() -> Self {
+		Self::with_overrides("").expect("embedded selectors.toml is invalid")
+	}
+
+	/// The default selectors, with any selectors in the toml text replacing them.
+	/// Fails if the text names a selector which does not exist, or any selector is not valid css.
+	pub fn with_overrides(overrides: &str) -> anyhow::Result<Self> {
+		let mut table = DEFAULTS.parse::<toml::Table>()?;
+		Self::merge(&mut table, overrides.parse::<toml::Table>()?, "")?;
+		Ok(toml::Value::Table(table).try_into::<Self>()?)
+	}
+
+	/// Loads the default selectors, overridden by the selectors in the toml file at the path (if any).
+	pub async fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+		let Some(path) = path else {
+			return Ok(Self::defaults());
+		};
+		let overrides = tokio::fs::read_to_string(path)
+			.await
+			.with_context(|| format!("reading selectors {path:?}"))?;
+		Self::with_overrides(&overrides).with_context(|| format!("loading selectors {path:?}"))
+	}
+
+	fn merge(table: &mut toml::Table, overrides: toml::Table, path: &str) -> anyhow::Result<()> {
+		for (key, value) in overrides.into_iter() {
+			let key_path = match path {
+				"" => key.clone(),
+				_ => format!("{path}.{key}"),
+			};
+			match (table.get_mut(&key), value) {
+				(None, _) => anyhow::bail!("Unknown selector {key_path:?}"),
+				(Some(toml::Value::Table(table)), toml::Value::Table(overrides)) => {
+					Self::merge(table, overrides, &key_path)?;
+				}
+				(Some(toml::Value::Table(_)), _) => {
+					anyhow::bail!("{key_path:?} is a section of selectors, not a selector")
+				}
+				(Some(existing), value) => *existing = value,
+			}
+		}
+		Ok(())
+	}
+
+	/// Makes these the selectors used by all parsers. Must be called before any page is parsed.
+	pub fn install(self) -> anyhow::Result<()> {
+		SELECTORS
+			.set(self)
+			.map_err(|_| anyhow::anyhow!("Selectors were already installed"))
+	}
+
+	/// The selectors in use, which are the defaults if none were installed.
+	pub fn get() -> &'static Self {
+		SELECTORS.get_or_init(Self::defaults)
+	}
+}
@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+
+mod attack;
+mod cache;
+mod crawl;
+mod dice;
+mod golden;
+mod listing_filter;
+mod mock_server;
+mod rate_limit;
+mod retry;
+mod speed;
+mod tidbit;
+mod validation;
+
+/// Set to regenerate the expected output of golden tests from what the code currently produces.
+static UPDATE_GOLDEN: &str = "UPDATE_GOLDEN";
+
+/// The directory of saved pages the tests run against.
+fn fixtures_dir() -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// Each file in a directory of the fixtures with the extension (e.g. `html`), sorted by name.
+fn fixtures(dir: &str, extension: &str) -> Vec<PathBuf> {
+	let pattern = fixtures_dir().join(dir).join(format!("*.{extension}"));
+	let mut paths = glob::glob(pattern.to_str().unwrap())
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	paths.sort();
+	paths
+}
+
+/// Compares the json of a value against the expected output saved at the path,
+/// returning a diff of the two if they differ.
+///
+/// If [`UPDATE_GOLDEN`] is set, the expected output is overwritten instead.
+fn compare_golden(expected_path: &Path, actual: &impl serde::Serialize) -> Result<(), String> {
+	let actual = serde_json::to_string_pretty(actual).unwrap() + "\n";
+	if std::env::var_os(UPDATE_GOLDEN).is_some() {
+		std::fs::write(expected_path, actual).unwrap();
+		return Ok(());
+	}
+	let Ok(expected) = std::fs::read_to_string(expected_path) else {
+		return Err(format!(
+			"{expected_path:?} does not exist, run with {UPDATE_GOLDEN}=1 to create it"
+		));
+	};
+	if expected == actual {
+		return Ok(());
+	}
+	let diff = similar::TextDiff::from_lines(&expected, &actual);
+	Err(format!(
+		"{expected_path:?} differs from the parsed output (run with {UPDATE_GOLDEN}=1 to accept it):\n{}",
+		diff.unified_diff().header("expected", "actual")
+	))
+}
+
+/// Fails with every difference found, so that one run reports all of the fixtures which changed.
+fn assert_no_differences(differences: Vec<String>) {
+	assert!(differences.is_empty(), "\n{}", differences.join("\n"));
+}
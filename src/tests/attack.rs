@@ -0,0 +1,68 @@
+//! Parses the damage of attacks, which is only the run of damage at the start of the hit.
+use crate::creature::attack::{Attack, DamageType};
+
+/// Each component of the damage as its printed average and type.
+fn damage(attack: &Attack) -> Vec<(u32, DamageType)> {
+	attack
+		.damage()
+		.iter()
+		.map(|damage| (damage.average(), damage.damage_type()))
+		.collect()
+}
+
+#[test]
+fn rider_damage_is_not_hit_damage() {
+	let attack = Attack::parse(
+		"Melee Weapon Attack: +4 to hit, reach 5 ft., one creature. Hit: 6 (1d6 + 3) piercing damage, \
+		and the target must succeed on a DC 13 Constitution saving throw or take 9 (2d8) poison damage.",
+	)
+	.unwrap()
+	.unwrap();
+	assert_eq!(damage(&attack), vec![(6, DamageType::Piercing)]);
+	assert!(attack.alternate_damage().is_empty());
+	assert_eq!(
+		attack.effect().map(String::as_str),
+		Some("and the target must succeed on a DC 13 Constitution saving throw or take 9 (2d8) poison damage.")
+	);
+}
+
+#[test]
+fn damage_joined_by_plus() {
+	let attack = Attack::parse(
+		"Melee Weapon Attack: +5 to hit, reach 5 ft., one target. Hit: 7 (1d8 + 3) piercing damage plus 7 (2d6) fire damage.",
+	)
+	.unwrap()
+	.unwrap();
+	assert_eq!(
+		damage(&attack),
+		vec![(7, DamageType::Piercing), (7, DamageType::Fire)]
+	);
+	assert_eq!(attack.effect(), None);
+}
+
+#[test]
+fn alternate_damage_and_effect() {
+	let attack = Attack::parse(
+		"Melee Weapon Attack: +4 to hit, reach 5 ft., one target. Hit: 5 (1d6 + 2) piercing damage, \
+		or 6 (1d8 + 2) piercing damage if used with two hands. The target is grappled (escape DC 12).",
+	)
+	.unwrap()
+	.unwrap();
+	assert_eq!(damage(&attack), vec![(5, DamageType::Piercing)]);
+	let alternates = attack.alternate_damage();
+	assert_eq!(alternates.len(), 1);
+	assert_eq!(alternates[0].condition(), "if used with two hands");
+	assert_eq!(
+		attack.effect().map(String::as_str),
+		Some("The target is grappled (escape DC 12).")
+	);
+}
+
+#[test]
+fn unknown_damage_type_is_not_an_attack() {
+	let attack = Attack::parse(
+		"Melee Weapon Attack: +6 to hit, reach 5 ft., one target. Hit: 10 (3d6) sonic damage.",
+	)
+	.unwrap();
+	assert!(attack.is_none());
+}
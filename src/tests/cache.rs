@@ -0,0 +1,125 @@
+//! Fetches pages from the [`MockServer`] through the on-disk response cache.
+use super::mock_server::{MockResponse, MockServer};
+use crate::dndbeyond::{
+	cache::{CacheMode, ResponseCache},
+	FetchFailed, WebpageProvider,
+};
+use std::{path::Path, time::Duration};
+
+static PAGE: &str = "/monsters/16921-guard";
+static BODY: &str = "<html><body>Guard</body></html>";
+static ETAG: &str = "\"guard-1\"";
+static LAST_MODIFIED: &str = "Sat, 17 Oct 2026 04:00:00 GMT";
+
+/// Fetches from the server through a cache in the directory, whose entries are fresh for the ttl.
+fn provider(server: &MockServer, cache_dir: &Path, ttl: Duration) -> WebpageProvider {
+	WebpageProvider::for_site(server.base_url().clone(), Vec::new())
+		.unwrap()
+		.with_cache(ResponseCache::new(
+			cache_dir.to_owned(),
+			ttl,
+			CacheMode::Normal,
+		))
+}
+
+async fn fetch(provider: &WebpageProvider, path: &str) -> Result<String, FetchFailed> {
+	let response = provider.fetch(provider.url(path).unwrap()).unwrap().await?;
+	Ok(response.text().await.unwrap())
+}
+
+fn page_with_validators() -> MockResponse {
+	MockResponse::page(BODY)
+		.with_header("ETag", ETAG)
+		.with_header("Last-Modified", LAST_MODIFIED)
+}
+
+#[tokio::test]
+async fn uses_fresh_entries_without_fetching() {
+	let server = MockServer::empty().await;
+	server.respond(PAGE, [MockResponse::page(BODY)]);
+	let dir = tempfile::tempdir().unwrap();
+	let provider = provider(&server, dir.path(), Duration::from_secs(3600));
+	let _workers = provider.spawn_workers(1);
+
+	assert_eq!(fetch(&provider, PAGE).await.unwrap(), BODY);
+	server.respond(PAGE, [MockResponse::page("<html>changed</html>")]);
+	assert_eq!(fetch(&provider, PAGE).await.unwrap(), BODY);
+	assert_eq!(server.requests(PAGE), 1);
+}
+
+#[tokio::test]
+async fn revalidates_stale_entries_with_their_validators() {
+	let server = MockServer::empty().await;
+	server.respond(PAGE, [page_with_validators()]);
+	let dir = tempfile::tempdir().unwrap();
+	let provider = provider(&server, dir.path(), Duration::ZERO);
+	let _workers = provider.spawn_workers(1);
+
+	fetch(&provider, PAGE).await.unwrap();
+	assert_eq!(server.last_request_header(PAGE, "if-none-match"), None);
+
+	fetch(&provider, PAGE).await.unwrap();
+	assert_eq!(server.requests(PAGE), 2);
+	assert_eq!(
+		server.last_request_header(PAGE, "if-none-match").as_deref(),
+		Some(ETAG)
+	);
+	assert_eq!(
+		server
+			.last_request_header(PAGE, "if-modified-since")
+			.as_deref(),
+		Some(LAST_MODIFIED)
+	);
+}
+
+#[tokio::test]
+async fn refreshes_entries_which_were_not_modified() {
+	let server = MockServer::empty().await;
+	server.respond(PAGE, [page_with_validators(), MockResponse::not_modified()]);
+	let dir = tempfile::tempdir().unwrap();
+	let ttl = Duration::from_secs(3600);
+	let provider = provider(&server, dir.path(), ttl);
+	let _workers = provider.spawn_workers(1);
+	fetch(&provider, PAGE).await.unwrap();
+
+	// Ages the entry past the ttl, so the next fetch revalidates it.
+	let cache = ResponseCache::new(dir.path().to_owned(), ttl, CacheMode::Normal);
+	let url = provider.url(PAGE).unwrap();
+	let (mut entry, _) = cache.get(&url).await.unwrap();
+	entry.fetched_at = 0;
+	let entry_paths = glob::glob(dir.path().join("*.json").to_str().unwrap())
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(entry_paths.len(), 1);
+	std::fs::write(&entry_paths[0], serde_json::to_string(&entry).unwrap()).unwrap();
+	assert!(!cache.is_fresh(&cache.get(&url).await.unwrap().0));
+
+	assert_eq!(fetch(&provider, PAGE).await.unwrap(), BODY);
+	assert_eq!(server.requests(PAGE), 2);
+	let (entry, body) = cache.get(&url).await.unwrap();
+	assert!(cache.is_fresh(&entry));
+	assert_eq!(body, BODY.as_bytes());
+
+	// So it is used again without asking the server.
+	assert_eq!(fetch(&provider, PAGE).await.unwrap(), BODY);
+	assert_eq!(server.requests(PAGE), 2);
+}
+
+#[tokio::test]
+async fn fails_when_not_modified_without_a_cached_copy() {
+	let server = MockServer::empty().await;
+	server.respond(PAGE, [MockResponse::not_modified()]);
+	let dir = tempfile::tempdir().unwrap();
+	let provider = provider(&server, dir.path(), Duration::from_secs(3600));
+	let _workers = provider.spawn_workers(1);
+
+	let error = fetch(&provider, PAGE).await.unwrap_err();
+	assert_eq!(error.status(), Some(reqwest::StatusCode::NOT_MODIFIED));
+	let cache = ResponseCache::new(
+		dir.path().to_owned(),
+		Duration::from_secs(3600),
+		CacheMode::Normal,
+	);
+	assert!(cache.get(&provider.url(PAGE).unwrap()).await.is_none());
+}
@@ -0,0 +1,416 @@
+//! Crawls the [`MockServer`] the same way `dndscrape fetch` crawls D&D Beyond.
+use super::mock_server::{MockResponse, MockServer};
+use crate::{
+	crawl,
+	creature::diagnostic::ParseMode,
+	database::{entity, Database},
+	dndbeyond::{listing_filter::ListingFilter, retry::RetryPolicy, WebpageProvider},
+	report::RunReport,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use std::{collections::BTreeSet, path::Path, time::Duration};
+
+static GUARD: &str = "/monsters/16921-guard";
+static SWARM_OF_RATS: &str = "/monsters/17003-swarm-of-rats";
+static WEREWOLF: &str = "/monsters/17062-werewolf";
+static BESTIAL_SPIRIT: &str = "/monsters/2560727-bestial-spirit";
+/// Listed on the second page, but has no page on the server.
+static MISSING_MONSTER: &str = "/monsters/99999-missing-monster";
+static SECOND_PAGE: &str = "/monsters?page=2&sort=cr";
+
+/// Fetches from the server, saving pages to the directory (rather than `target/monsters`, which tests running
+/// in parallel would share).
+fn provider(server: &MockServer, retry: RetryPolicy, page_dir: &Path) -> WebpageProvider {
+	WebpageProvider::for_site(server.base_url().clone(), Vec::new())
+		.unwrap()
+		.with_retry_policy(retry)
+		.with_page_dir(page_dir)
+}
+
+fn quick_retries() -> RetryPolicy {
+	RetryPolicy::new(2, Duration::from_millis(10), Duration::from_millis(10))
+}
+
+async fn run(
+	server: &MockServer,
+	retry: RetryPolicy,
+	database: &Database,
+	page_dir: &Path,
+	resume: bool,
+	parse_mode: ParseMode,
+) -> serde_json::Value {
+	run_filtered(
+		server,
+		retry,
+		database,
+		page_dir,
+		ListingFilter::default(),
+		resume,
+		parse_mode,
+	)
+	.await
+}
+
+async fn run_filtered(
+	server: &MockServer,
+	retry: RetryPolicy,
+	database: &Database,
+	page_dir: &Path,
+	filter: ListingFilter,
+	resume: bool,
+	parse_mode: ParseMode,
+) -> serde_json::Value {
+	let provider = provider(server, retry, page_dir);
+	let report: RunReport = crawl(provider, database, filter, resume, false, parse_mode)
+		.await
+		.unwrap();
+	serde_json::to_value(&report).unwrap()
+}
+
+/// The urls of the creatures which failed in a report.
+fn failed_urls(report: &serde_json::Value) -> BTreeSet<&str> {
+	report["failures"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|failure| failure["url"].as_str().unwrap())
+		.collect()
+}
+
+#[tokio::test]
+async fn crawls_every_listing_page() {
+	let server = MockServer::with_fixtures().await;
+	let guard = server.response(GUARD).unwrap();
+	server.respond(GUARD, [guard.with_delay(Duration::from_millis(300))]);
+	let werewolf = server.response(WEREWOLF).unwrap();
+	server.respond(WEREWOLF, [MockResponse::status(503), werewolf]);
+
+	let dir = tempfile::tempdir().unwrap();
+	let database = Database::open(&dir.path().join("creatures.db"))
+		.await
+		.unwrap();
+	let report = run(
+		&server,
+		quick_retries(),
+		&database,
+		dir.path(),
+		false,
+		ParseMode::Strict,
+	)
+	.await;
+
+	assert_eq!(report["added"], 3, "{report:#}");
+	assert_eq!(report["failed"], 2, "{report:#}");
+	// The bestial spirit's hit points are a formula (so it fails to parse strictly),
+	// and the missing monster is not found.
+	assert_eq!(
+		failed_urls(&report),
+		BTreeSet::from([BESTIAL_SPIRIT, MISSING_MONSTER])
+	);
+	// The werewolf only succeeds once its unavailable response is retried.
+	assert_eq!(server.requests(WEREWOLF), 2);
+	assert_eq!(server.requests(MISSING_MONSTER), 1);
+	assert!(dir.path().join("16921-guard.html").exists());
+	let stored = database.stored_listings().await.unwrap();
+	assert_eq!(stored.len(), 3);
+	// Only the werewolf has another armor class, in its other forms.
+	let alternates = entity::armor_class_alternate::Entity::find()
+		.all(database.connection())
+		.await
+		.unwrap();
+	assert_eq!(
+		alternates
+			.iter()
+			.map(|alternate| (
+				alternate.value,
+				alternate.source.as_deref(),
+				alternate.condition.as_str()
+			))
+			.collect::<Vec<_>>(),
+		[(12, Some("Natural Armor"), "In Wolf or Hybrid Form")]
+	);
+	// Walking speeds are stored with their mode, as only speeds which were not understood have none.
+	let werewolf = entity::creature::Entity::find()
+		.filter(entity::creature::Column::Url.eq(WEREWOLF))
+		.one(database.connection())
+		.await
+		.unwrap()
+		.unwrap();
+	let speeds = entity::speed::Entity::find()
+		.filter(entity::speed::Column::CreatureId.eq(werewolf.id))
+		.all(database.connection())
+		.await
+		.unwrap();
+	assert_eq!(
+		speeds
+			.iter()
+			.map(|speed| (
+				speed.mode.as_deref(),
+				speed.distance,
+				speed.condition.as_deref()
+			))
+			.collect::<Vec<_>>(),
+		[
+			(Some("walk"), Some(30), None),
+			(Some("walk"), Some(40), Some("in wolf form"))
+		]
+	);
+
+	// Crawling again only fetches the creatures which were not saved.
+	let report = run(
+		&server,
+		quick_retries(),
+		&database,
+		dir.path(),
+		false,
+		ParseMode::Strict,
+	)
+	.await;
+	assert_eq!(report["added"], 0, "{report:#}");
+	assert_eq!(report["unchanged"], 3, "{report:#}");
+	assert_eq!(report["failed"], 2, "{report:#}");
+	assert_eq!(server.requests(GUARD), 1);
+	assert_eq!(server.requests(SWARM_OF_RATS), 1);
+	assert_eq!(server.requests(BESTIAL_SPIRIT), 2);
+}
+
+#[tokio::test]
+async fn resumes_after_a_listing_page_fails() {
+	let server = MockServer::with_fixtures().await;
+	let second_page = server.response(SECOND_PAGE).unwrap();
+	server.respond(SECOND_PAGE, [MockResponse::status(500)]);
+
+	let dir = tempfile::tempdir().unwrap();
+	let database = Database::open(&dir.path().join("creatures.db"))
+		.await
+		.unwrap();
+	let report = run(
+		&server,
+		RetryPolicy::none(),
+		&database,
+		dir.path(),
+		false,
+		ParseMode::Strict,
+	)
+	.await;
+
+	// Only the creatures on the first page are fetched.
+	assert_eq!(report["added"], 2, "{report:#}");
+	assert_eq!(report["failed"], 0, "{report:#}");
+	assert!(database.checkpoint().has_failed_pages().await.unwrap());
+
+	// Resuming skips the first page and its creatures.
+	server.respond(SECOND_PAGE, [second_page]);
+	let report = run(
+		&server,
+		RetryPolicy::none(),
+		&database,
+		dir.path(),
+		true,
+		ParseMode::Lenient,
+	)
+	.await;
+	assert_eq!(report["added"], 2, "{report:#}");
+	assert_eq!(report["failed"], 1, "{report:#}");
+	assert_eq!(failed_urls(&report), BTreeSet::from([MISSING_MONSTER]));
+	assert_eq!(server.requests("/monsters?page=1&sort=cr"), 1);
+	assert_eq!(server.requests(GUARD), 1);
+	assert_eq!(server.requests(SECOND_PAGE), 2);
+	assert!(!database.checkpoint().has_failed_pages().await.unwrap());
+	assert_eq!(database.stored_listings().await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn finds_creatures_by_their_listing() {
+	let server = MockServer::with_fixtures().await;
+	// The stat block links to a different url than the listing did.
+	let html = std::fs::read_to_string(super::fixtures_dir().join("creatures/guard.html")).unwrap();
+	let html = html.replace(
+		r#"href="/monsters/16921-guard""#,
+		r#"href="https://www.dndbeyond.com/monsters/16921-guard""#,
+	);
+	server.respond(GUARD, [MockResponse::page(html)]);
+
+	let dir = tempfile::tempdir().unwrap();
+	let database = Database::open(&dir.path().join("creatures.db"))
+		.await
+		.unwrap();
+	let report = run(
+		&server,
+		quick_retries(),
+		&database,
+		dir.path(),
+		false,
+		ParseMode::Lenient,
+	)
+	.await;
+	assert_eq!(report["added"], 4, "{report:#}");
+	assert!(database
+		.stored_listings()
+		.await
+		.unwrap()
+		.contains_key(&std::path::PathBuf::from(GUARD)));
+
+	// So crawling again finds the guard unchanged, rather than fetching it again.
+	let report = run(
+		&server,
+		quick_retries(),
+		&database,
+		dir.path(),
+		false,
+		ParseMode::Lenient,
+	)
+	.await;
+	assert_eq!(report["unchanged"], 4, "{report:#}");
+	assert_eq!(report["removed"], 0, "{report:#}");
+	assert_eq!(server.requests(GUARD), 1);
+}
+
+#[tokio::test]
+async fn resumes_without_the_listings_the_filter_skipped() {
+	let server = MockServer::with_fixtures().await;
+	let second_page = server.response(SECOND_PAGE).unwrap();
+	server.respond(SECOND_PAGE, [MockResponse::status(500)]);
+	let filter = || ListingFilter::default().with_name_glob("guard").unwrap();
+
+	let dir = tempfile::tempdir().unwrap();
+	let database = Database::open(&dir.path().join("creatures.db"))
+		.await
+		.unwrap();
+	let report = run_filtered(
+		&server,
+		RetryPolicy::none(),
+		&database,
+		dir.path(),
+		filter(),
+		false,
+		ParseMode::Strict,
+	)
+	.await;
+	assert_eq!(report["added"], 1, "{report:#}");
+	// The swarm of rats on the first page was skipped, so is not left to fetch.
+	assert!(database
+		.checkpoint()
+		.unfinished_creatures()
+		.await
+		.unwrap()
+		.is_empty());
+
+	// Resuming without the filter only fetches the creatures on the page which failed.
+	server.respond(SECOND_PAGE, [second_page]);
+	let report = run(
+		&server,
+		RetryPolicy::none(),
+		&database,
+		dir.path(),
+		true,
+		ParseMode::Lenient,
+	)
+	.await;
+	assert_eq!(report["added"], 2, "{report:#}");
+	assert_eq!(report["failed"], 1, "{report:#}");
+	assert_eq!(server.requests(GUARD), 1);
+	assert_eq!(server.requests(SWARM_OF_RATS), 0);
+	assert_eq!(server.requests(BESTIAL_SPIRIT), 1);
+}
+
+#[tokio::test]
+async fn refetches_creatures_saved_with_an_older_shape() {
+	use crate::export::{export, ExportFormat};
+	use sea_orm::{ColumnTrait, QueryFilter};
+
+	let server = MockServer::with_fixtures().await;
+	let dir = tempfile::tempdir().unwrap();
+	let database = Database::open(&dir.path().join("creatures.db"))
+		.await
+		.unwrap();
+	run(
+		&server,
+		quick_retries(),
+		&database,
+		dir.path(),
+		false,
+		ParseMode::Lenient,
+	)
+	.await;
+	let output = dir.path().join("export");
+	let exported = export(&database, ExportFormat::Json, &output, false)
+		.await
+		.unwrap();
+	assert_eq!(exported, 4);
+
+	// As if the guard was saved before the current shape of the data.
+	entity::creature::Entity::update_many()
+		.col_expr(
+			entity::creature::Column::DataVersion,
+			sea_orm::sea_query::Expr::value(Option::<i32>::None),
+		)
+		.filter(entity::creature::Column::Url.eq(GUARD))
+		.exec(database.connection())
+		.await
+		.unwrap();
+
+	// Its stale data is not exported, since it may not match the schema.
+	let exported = export(&database, ExportFormat::Json, &output, false)
+		.await
+		.unwrap();
+	assert_eq!(exported, 3);
+
+	// And crawling again fetches it, even though its listing is unchanged.
+	let report = run(
+		&server,
+		quick_retries(),
+		&database,
+		dir.path(),
+		false,
+		ParseMode::Lenient,
+	)
+	.await;
+	assert_eq!(report["changed"], 1, "{report:#}");
+	assert_eq!(report["unchanged"], 3, "{report:#}");
+	assert_eq!(server.requests(GUARD), 2);
+	let exported = export(&database, ExportFormat::Json, &output, false)
+		.await
+		.unwrap();
+	assert_eq!(exported, 4);
+}
+
+#[tokio::test]
+async fn finishes_creature_pages_when_the_listing_fails() {
+	let server = MockServer::with_fixtures().await;
+	let guard = server.response(GUARD).unwrap();
+	server.respond(GUARD, [MockResponse::status(404)]);
+	let dir = tempfile::tempdir().unwrap();
+	let database = Database::open(&dir.path().join("creatures.db"))
+		.await
+		.unwrap();
+	let report = run(
+		&server,
+		quick_retries(),
+		&database,
+		dir.path(),
+		false,
+		ParseMode::Lenient,
+	)
+	.await;
+	assert!(failed_urls(&report).contains(GUARD), "{report:#}");
+
+	// Resuming fetches the guard again, but the listing can no longer be fetched (and fails before the guard).
+	server.respond(GUARD, [guard.with_delay(Duration::from_millis(200))]);
+	server.respond("/monsters", [MockResponse::status(404)]);
+	let provider = provider(&server, quick_retries(), dir.path());
+	let result = crawl(
+		provider,
+		&database,
+		ListingFilter::default(),
+		true,
+		false,
+		ParseMode::Lenient,
+	)
+	.await;
+	assert!(result.is_err());
+	// The crawl waited for the guard to be saved before failing, rather than leaving it running.
+	let stored = database.stored_listings().await.unwrap();
+	assert!(stored.contains_key(&std::path::PathBuf::from(GUARD)));
+	assert_eq!(server.requests(GUARD), 2);
+}
@@ -0,0 +1,166 @@
+//! Parses dice expressions, and checks their statistics and rolls, including the recharge rolls of abilities.
+use crate::creature::{
+	dice::{DiceExpression, DiceTerm},
+	recharge::Recharge,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn dice(text: &str) -> DiceExpression {
+	text.parse::<DiceExpression>().unwrap()
+}
+
+/// Whether two chances are the same, give or take floating point error.
+fn assert_chance(actual: f64, expected: f64) {
+	assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+#[test]
+fn parses_sums_of_dice_and_constants() {
+	let expression = dice("2d6 + 1d8 + 4");
+	assert_eq!(
+		*expression.terms(),
+		[
+			DiceTerm::Dice { count: 2, sides: 6 },
+			DiceTerm::Dice { count: 1, sides: 8 },
+			DiceTerm::Constant { value: 4 },
+		]
+	);
+	assert_eq!(expression.to_string(), "2d6 + 1d8 + 4");
+	assert_eq!(expression.minimum(), 7);
+	assert_eq!(expression.maximum(), 24);
+	assert_eq!(expression.average(), 15.5);
+
+	// Subtracted dice have a negative count.
+	let expression = dice("2d6 - 1d4");
+	assert_eq!(
+		*expression.terms(),
+		[
+			DiceTerm::Dice { count: 2, sides: 6 },
+			DiceTerm::Dice {
+				count: -1,
+				sides: 4
+			},
+		]
+	);
+	assert_eq!(expression.to_string(), "2d6 - 1d4");
+	assert_eq!(expression.minimum(), -2);
+	assert_eq!(expression.maximum(), 11);
+	assert_eq!(expression.average(), 4.5);
+
+	// A die without a count is a single die.
+	let expression = dice("d6");
+	assert_eq!(*expression.terms(), [DiceTerm::Dice { count: 1, sides: 6 }]);
+	assert_eq!(expression.to_string(), "1d6");
+	assert_eq!((expression.minimum(), expression.maximum()), (1, 6));
+	assert_eq!(expression.average(), 3.5);
+}
+
+#[test]
+fn rejects_invalid_dice() {
+	for text in ["1d0", "", "2d6 3", "2d6 + fire"] {
+		assert!(
+			text.parse::<DiceExpression>().is_err(),
+			"{text:?} should not parse"
+		);
+	}
+	let error = "1d0".parse::<DiceExpression>().unwrap_err();
+	assert!(error.to_string().contains("at least one side"), "{error}");
+
+	// Rather than overflowing when the totals are worked out.
+	for text in ["99999d99999", "101d6", "1d101", "2d6 + 99999999999"] {
+		assert!(
+			text.parse::<DiceExpression>().is_err(),
+			"{text:?} should not parse"
+		);
+	}
+}
+
+#[test]
+fn only_works_out_chances_of_small_distributions() {
+	let expression = dice("100d100 + 2147483647");
+	assert_eq!(expression.minimum(), i32::MAX);
+	assert_eq!(expression.maximum(), i32::MAX);
+	assert_eq!(expression.chance_at_least(0), None);
+
+	let expression = dice("33d20 + 330");
+	assert_eq!((expression.minimum(), expression.maximum()), (363, 990));
+	assert_chance(expression.chance_at_least(363).unwrap(), 1.0);
+}
+
+#[test]
+fn chances_follow_the_distribution_of_the_dice() {
+	let expression = dice("2d6");
+	assert_chance(expression.chance_at_least(2).unwrap(), 1.0);
+	assert_chance(expression.chance_at_least(7).unwrap(), 21.0 / 36.0);
+	assert_chance(expression.chance_at_least(12).unwrap(), 1.0 / 36.0);
+	assert_chance(expression.chance_at_least(13).unwrap(), 0.0);
+
+	let expression = dice("1d4 - 1d4 + 1");
+	assert_chance(expression.chance_at_least(-2).unwrap(), 1.0);
+	assert_chance(expression.chance_at_least(4).unwrap(), 1.0 / 16.0);
+}
+
+#[test]
+fn seeded_rolls_are_repeatable() {
+	let expression = dice("2d6 - 1d4 + 3");
+	let rolls = |seed: u64| {
+		let mut rng = StdRng::seed_from_u64(seed);
+		(0..1000)
+			.map(|_| expression.roll(&mut rng))
+			.collect::<Vec<_>>()
+	};
+	let first = rolls(7);
+	assert_eq!(first, rolls(7));
+	assert_ne!(first, rolls(8));
+	assert!(first
+		.iter()
+		.all(|roll| (expression.minimum()..=expression.maximum()).contains(roll)));
+	// Every total is rolled at some point, and the rolls average out near the average.
+	for total in expression.minimum()..=expression.maximum() {
+		assert!(first.contains(&total), "{total} was never rolled");
+	}
+	let mean = first.iter().sum::<i32>() as f64 / first.len() as f64;
+	assert!((mean - expression.average()).abs() < 0.25, "{mean}");
+}
+
+#[test]
+fn recharges_on_the_printed_range() {
+	let recharge = Recharge::find("Fire Breath (Recharge 5\u{2013}6)")
+		.unwrap()
+		.unwrap();
+	assert_eq!(recharge.minimum(), 5);
+	assert_chance(recharge.chance(), 1.0 / 3.0);
+
+	let recharge = Recharge::find("Rotting Gaze (Recharge 6)")
+		.unwrap()
+		.unwrap();
+	assert_chance(recharge.chance(), 1.0 / 6.0);
+
+	assert!(
+		Recharge::find("Wind Walk (Recharges after a Short or Long Rest)")
+			.unwrap()
+			.is_none()
+	);
+	assert!(Recharge::find("Breath (Recharge 5-7)").is_err());
+}
+
+#[test]
+fn seeded_recharge_rolls_are_repeatable() {
+	let recharge = Recharge::find("Fire Breath (Recharge 5-6)")
+		.unwrap()
+		.unwrap();
+	let rolls = |seed: u64| {
+		let mut rng = StdRng::seed_from_u64(seed);
+		(0..3000)
+			.map(|_| recharge.roll(&mut rng))
+			.collect::<Vec<_>>()
+	};
+	let first = rolls(42);
+	assert_eq!(first, rolls(42));
+	let recharged = first.iter().filter(|recharged| **recharged).count() as f64;
+	assert!(
+		(recharged / first.len() as f64 - recharge.chance()).abs() < 0.05,
+		"recharged {recharged} of {} rolls",
+		first.len()
+	);
+}
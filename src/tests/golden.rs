@@ -0,0 +1,106 @@
+//! Parses the saved pages in `fixtures/` and compares the results against the expected output saved beside them.
+//!
+//! - `fixtures/creatures/<name>.html` is a creature's page (with its listing in `<name>.listing.json`),
+//!   whose creature is parsed leniently and expected to match `<name>.json`.
+//! - `fixtures/listings/<name>.html` is a page of the monster listing,
+//!   whose last page and rows are expected to match `<name>.json`.
+use super::{assert_no_differences, compare_golden, fixtures};
+use crate::{
+	creature::diagnostic::ParseMode,
+	dndbeyond::creature_list::{CreatureListing, CreatureListingPage, PageIter},
+	report::Failure,
+};
+
+/// The fields each creature page is expected to have diagnostics for, by the name of the page.
+/// Every other page is expected to parse without any, so a regression can't be accepted into the golden output.
+static EXPECTED_DIAGNOSTICS: &[(&str, &[&str])] = &[(
+	// A spell's summon, whose stat block scales with the level of the spell.
+	"bestial-spirit",
+	&["hit points", "proficiency bonus"],
+)];
+
+#[tokio::test]
+async fn creature_pages() {
+	let html_paths = fixtures("creatures", "html");
+	assert!(!html_paths.is_empty(), "no creature fixtures found");
+
+	let mut differences = Vec::new();
+	for html_path in html_paths {
+		let creature = CreatureListing::parse_saved(&html_path, ParseMode::Lenient)
+			.await
+			.unwrap_or_else(|err| panic!("{err:?}"));
+		let name = html_path.file_stem().unwrap().to_str().unwrap();
+		let expected_fields = EXPECTED_DIAGNOSTICS
+			.iter()
+			.find(|(page, _)| *page == name)
+			.map_or(&[][..], |(_, fields)| *fields);
+		let fields = creature
+			.diagnostics()
+			.iter()
+			.map(|diagnostic| diagnostic.field().as_str())
+			.collect::<Vec<_>>();
+		if fields != expected_fields {
+			differences.push(format!(
+				"{html_path:?} has diagnostics for {fields:?}, but expected {expected_fields:?}: {:?}",
+				creature.diagnostics()
+			));
+		}
+		// Strict parsing fails on exactly the pages lenient parsing has diagnostics for.
+		let strict = CreatureListing::parse_saved(&html_path, ParseMode::Strict).await;
+		if strict.is_ok() != creature.diagnostics().is_empty() {
+			differences.push(format!(
+				"{html_path:?} parsed strictly as {strict:?}, but leniently with diagnostics {:?}",
+				creature.diagnostics()
+			));
+		}
+		// And the run report names the field it first failed on.
+		if let (Err(err), Some(diagnostic)) = (&strict, creature.diagnostics().first()) {
+			let failure = serde_json::to_value(Failure::new(None, err, None)).unwrap();
+			if failure["field"] != diagnostic.field().as_str() {
+				differences.push(format!(
+					"{html_path:?} failed strictly on field {}, but its first diagnostic is {diagnostic}",
+					failure["field"]
+				));
+			}
+		}
+		if let Err(diff) = compare_golden(&html_path.with_extension("json"), &creature) {
+			differences.push(diff);
+		}
+	}
+	assert_no_differences(differences);
+}
+
+/// The parsed contents of a page of the monster listing.
+/// Errors are kept as their messages, so pages with unparseable rows can be checked too.
+#[derive(serde::Serialize)]
+struct ListingPageOutput {
+	last_page: Result<usize, String>,
+	rows: Vec<Result<CreatureListing, String>>,
+}
+
+#[test]
+fn listing_pages() {
+	let html_paths = fixtures("listings", "html");
+	assert!(!html_paths.is_empty(), "no listing fixtures found");
+
+	let url = reqwest::Url::parse("https://www.dndbeyond.com/monsters?page=1&sort=cr").unwrap();
+	let mut differences = Vec::new();
+	for html_path in html_paths {
+		let body = std::fs::read_to_string(&html_path).unwrap();
+		let page = CreatureListingPage::new(&url, &body);
+		let list = page.list().unwrap_or_else(|err| panic!("{err}"));
+		let output = ListingPageOutput {
+			last_page: PageIter::find_max_page_count(url.as_str(), &body)
+				.map_err(|err| err.to_string()),
+			rows: list
+				.children()
+				.into_iter()
+				.map(|row| CreatureListing::try_from(row).map_err(|err| err.to_string()))
+				.collect(),
+		};
+		if let Err(diff) = compare_golden(&html_path.with_extension("json"), &output) {
+			differences.push(diff);
+		}
+	}
+	assert_no_differences(differences);
+}
@@ -0,0 +1,41 @@
+//! Selects listings by their challenge rating, the same as `dndscrape fetch --min-cr --max-cr`.
+use crate::{
+	creature::challenge_rating::ChallengeRating,
+	dndbeyond::{creature_list::CreatureListing, listing_filter::ListingFilter},
+};
+
+fn listing(name: &str) -> CreatureListing {
+	let path = super::fixtures_dir().join(format!("creatures/{name}.listing.json"));
+	serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn rating(text: &str) -> Option<ChallengeRating> {
+	Some(text.parse::<ChallengeRating>().unwrap())
+}
+
+#[test]
+fn matches_challenge_ratings_within_the_bounds() {
+	// The guard is 1/8, and the werewolf is 3.
+	let guard = listing("guard");
+	let werewolf = listing("werewolf");
+
+	let filter = ListingFilter::default()
+		.with_challenge_rating(rating("1/8"), rating("1"))
+		.unwrap();
+	assert!(filter.matches(&guard));
+	assert!(!filter.matches(&werewolf));
+
+	let filter = ListingFilter::default()
+		.with_challenge_rating(rating("1"), None)
+		.unwrap();
+	assert!(!filter.matches(&guard));
+	assert!(filter.matches(&werewolf));
+}
+
+#[test]
+fn rejects_a_minimum_above_the_maximum() {
+	let error = ListingFilter::default()
+		.with_challenge_rating(rating("5"), rating("1/4"))
+		.unwrap_err();
+	assert!(error.to_string().contains("greater than"), "{error}");
+}
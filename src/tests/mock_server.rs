@@ -0,0 +1,234 @@
+//! An in-process stand-in for D&D Beyond, so that crawls can be tested end-to-end without the network.
+use super::{fixtures, fixtures_dir};
+use crate::dndbeyond::creature_list::CreatureListing;
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+use tokio::{
+	io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+	net::{TcpListener, TcpStream},
+};
+
+/// A response the mock server sends for a path.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+	status: reqwest::StatusCode,
+	headers: Vec<(String, String)>,
+	body: String,
+	delay: Duration,
+}
+impl MockResponse {
+	/// A successful response with the html of a page.
+	pub fn page(body: impl Into<String>) -> Self {
+		Self {
+			status: reqwest::StatusCode::OK,
+			headers: Vec::new(),
+			body: body.into(),
+			delay: Duration::ZERO,
+		}
+	}
+
+	/// A `304 Not Modified` response, which has no body.
+	pub fn not_modified() -> Self {
+		Self {
+			status: reqwest::StatusCode::NOT_MODIFIED,
+			headers: Vec::new(),
+			body: String::new(),
+			delay: Duration::ZERO,
+		}
+	}
+
+	/// An unsuccessful response (e.g. `404` or `503`).
+	pub fn status(status: u16) -> Self {
+		let status = reqwest::StatusCode::from_u16(status).unwrap();
+		Self {
+			status,
+			headers: Vec::new(),
+			body: format!("<html><body><h1>{status}</h1></body></html>"),
+			delay: Duration::ZERO,
+		}
+	}
+
+	/// Sends a header with the response (e.g. `ETag`).
+	pub fn with_header(mut self, name: &str, value: &str) -> Self {
+		self.headers.push((name.to_owned(), value.to_owned()));
+		self
+	}
+
+	/// Waits before sending the response, like a slow site.
+	pub fn with_delay(mut self, delay: Duration) -> Self {
+		self.delay = delay;
+		self
+	}
+}
+
+/// The responses for a path, which are sent in order with the last repeated for every request after.
+#[derive(Default)]
+struct Route {
+	responses: VecDeque<MockResponse>,
+	requests: usize,
+	/// The headers of the last request, by their lowercase name.
+	last_headers: HashMap<String, String>,
+}
+impl Route {
+	fn next_response(&mut self, headers: HashMap<String, String>) -> Option<MockResponse> {
+		self.requests += 1;
+		self.last_headers = headers;
+		match self.responses.len() {
+			0 | 1 => self.responses.front().cloned(),
+			_ => self.responses.pop_front(),
+		}
+	}
+}
+
+/// Serves pages over http on a local port, by their path (and query).
+/// Paths without a route are `404 Not Found`.
+///
+/// The server stops when it is dropped.
+pub struct MockServer {
+	base_url: reqwest::Url,
+	routes: Arc<Mutex<HashMap<String, Route>>>,
+	task: tokio::task::JoinHandle<()>,
+}
+impl MockServer {
+	/// Starts a server with no routes.
+	pub async fn empty() -> Self {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let base_url = format!("http://{}", listener.local_addr().unwrap());
+		let routes = Arc::new(Mutex::new(HashMap::<String, Route>::new()));
+		let task = tokio::task::spawn({
+			let routes = routes.clone();
+			async move {
+				while let Ok((stream, _)) = listener.accept().await {
+					tokio::task::spawn(Self::serve(stream, routes.clone()));
+				}
+			}
+		});
+		Self {
+			base_url: reqwest::Url::parse(&base_url).unwrap(),
+			routes,
+			task,
+		}
+	}
+
+	/// Starts a server which mirrors the monster listing and creature pages of the site:
+	/// - each listing page in `fixtures/server` (`page-<n>.html`) at `/monsters?page=<n>&sort=cr`,
+	///   with the first page also at `/monsters`.
+	/// - each creature page in `fixtures/creatures` at the url in its listing.
+	pub async fn with_fixtures() -> Self {
+		let server = Self::empty().await;
+		for html_path in fixtures("server", "html") {
+			let body = std::fs::read_to_string(&html_path).unwrap();
+			let page = html_path.file_stem().unwrap().to_str().unwrap();
+			let page_idx = page.strip_prefix("page-").unwrap();
+			if page_idx == "1" {
+				server.respond("/monsters", [MockResponse::page(body.clone())]);
+			}
+			let path = format!("/monsters?page={page_idx}&sort=cr");
+			server.respond(&path, [MockResponse::page(body)]);
+		}
+		for html_path in fixtures("creatures", "html") {
+			let sidecar_path = CreatureListing::sidecar_path(&html_path);
+			let sidecar = std::fs::read_to_string(sidecar_path).unwrap();
+			let listing = serde_json::from_str::<CreatureListing>(&sidecar).unwrap();
+			let body = std::fs::read_to_string(&html_path).unwrap();
+			let path = listing.url().to_str().unwrap();
+			server.respond(path, [MockResponse::page(body)]);
+		}
+		assert!(
+			server.response("/monsters").is_some(),
+			"no listing pages found in {:?}",
+			fixtures_dir().join("server")
+		);
+		server
+	}
+
+	/// The url of the server, to fetch pages from instead of D&D Beyond.
+	pub fn base_url(&self) -> &reqwest::Url {
+		&self.base_url
+	}
+
+	/// Replaces the responses for the path, which are sent in order (the last is sent for every request after).
+	/// The requests already received for the path are still counted.
+	pub fn respond(&self, path: &str, responses: impl IntoIterator<Item = MockResponse>) {
+		let mut routes = self.routes.lock().unwrap();
+		let route = routes.entry(path.to_owned()).or_default();
+		route.responses = responses.into_iter().collect();
+	}
+
+	/// The last response for the path (e.g. its fixture page), if it has any.
+	pub fn response(&self, path: &str) -> Option<MockResponse> {
+		let routes = self.routes.lock().unwrap();
+		routes.get(path)?.responses.back().cloned()
+	}
+
+	/// The number of requests received for the path.
+	pub fn requests(&self, path: &str) -> usize {
+		let routes = self.routes.lock().unwrap();
+		routes
+			.get(path)
+			.map(|route| route.requests)
+			.unwrap_or_default()
+	}
+
+	/// The value of a header (e.g. `if-none-match`) in the last request received for the path, if it had it.
+	pub fn last_request_header(&self, path: &str, name: &str) -> Option<String> {
+		let routes = self.routes.lock().unwrap();
+		routes.get(path)?.last_headers.get(name).cloned()
+	}
+
+	/// Reads a request from the connection and writes the response for its path.
+	/// Each connection serves a single request.
+	async fn serve(stream: TcpStream, routes: Arc<Mutex<HashMap<String, Route>>>) {
+		let mut stream = BufReader::new(stream);
+		let mut request_line = String::new();
+		if stream.read_line(&mut request_line).await.is_err() {
+			return;
+		}
+		let mut headers = HashMap::new();
+		loop {
+			let mut header = String::new();
+			match stream.read_line(&mut header).await {
+				Ok(0) | Err(_) => return,
+				Ok(_) if header.trim().is_empty() => break,
+				Ok(_) => {
+					if let Some((name, value)) = header.split_once(':') {
+						headers.insert(name.trim().to_lowercase(), value.trim().to_owned());
+					}
+				}
+			}
+		}
+
+		// e.g. `GET /monsters?page=1&sort=cr HTTP/1.1`
+		let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+		let response = routes
+			.lock()
+			.unwrap()
+			.entry(path.to_owned())
+			.or_default()
+			.next_response(headers)
+			.unwrap_or_else(|| MockResponse::status(404));
+
+		tokio::time::sleep(response.delay).await;
+		let mut head = format!(
+			"HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+			response.status,
+			response.body.len()
+		);
+		for (name, value) in response.headers.iter() {
+			head += &format!("{name}: {value}\r\n");
+		}
+		head += "\r\n";
+		let stream = stream.get_mut();
+		let _ = stream.write_all(head.as_bytes()).await;
+		let _ = stream.write_all(response.body.as_bytes()).await;
+		let _ = stream.shutdown().await;
+	}
+}
+impl Drop for MockServer {
+	fn drop(&mut self) {
+		self.task.abort();
+	}
+}
@@ -0,0 +1,46 @@
+//! Sends requests through the rate limiter with tokio's clock paused, so the waits can be measured exactly.
+use crate::dndbeyond::rate_limit::RateLimiter;
+use std::time::Duration;
+use tokio::time::Instant;
+
+fn url(host: &str) -> reqwest::Url {
+	reqwest::Url::parse(&format!("https://{host}/monsters")).unwrap()
+}
+
+/// Asserts the time since the start is the expected duration, give or take the rounding of the refill.
+fn assert_elapsed(start: Instant, expected: Duration) {
+	let elapsed = start.elapsed();
+	let difference = elapsed.max(expected) - elapsed.min(expected);
+	assert!(
+		difference < Duration::from_millis(1),
+		"waited {elapsed:?} rather than {expected:?}"
+	);
+}
+
+#[tokio::test(start_paused = true)]
+async fn waits_for_tokens_beyond_the_burst() {
+	let limiter = RateLimiter::new(4.0, 2);
+	let start = Instant::now();
+	limiter.acquire(&url("a.example")).await;
+	limiter.acquire(&url("a.example")).await;
+	assert_elapsed(start, Duration::ZERO);
+
+	// Each request after the burst waits for a token to refill, which takes 1/rate.
+	limiter.acquire(&url("a.example")).await;
+	assert_elapsed(start, Duration::from_millis(250));
+	limiter.acquire(&url("a.example")).await;
+	assert_elapsed(start, Duration::from_millis(500));
+}
+
+#[tokio::test(start_paused = true)]
+async fn hosts_have_separate_buckets() {
+	let limiter = RateLimiter::new(1.0, 1);
+	let start = Instant::now();
+	limiter.acquire(&url("a.example")).await;
+	// The bucket of the first host is empty, but the second host has its own.
+	limiter.acquire(&url("b.example")).await;
+	assert_elapsed(start, Duration::ZERO);
+
+	limiter.acquire(&url("a.example")).await;
+	assert_elapsed(start, Duration::from_secs(1));
+}
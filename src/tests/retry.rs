@@ -0,0 +1,41 @@
+//! Computes the waits between retries, including those the site asks for with `Retry-After`.
+use crate::dndbeyond::retry::RetryPolicy;
+use std::time::{Duration, SystemTime};
+
+fn policy() -> RetryPolicy {
+	RetryPolicy::new(3, Duration::from_millis(500), Duration::from_secs(30))
+}
+
+fn retry_after(value: &str) -> Option<Duration> {
+	let mut headers = reqwest::header::HeaderMap::new();
+	headers.insert(reqwest::header::RETRY_AFTER, value.parse().unwrap());
+	RetryPolicy::retry_after(&headers)
+}
+
+#[test]
+fn uses_retry_after_up_to_the_max_delay() {
+	let policy = policy();
+	let delay = policy.delay(0, retry_after("5"));
+	assert_eq!(delay, Duration::from_secs(5));
+
+	let delay = policy.delay(0, retry_after("86400"));
+	assert_eq!(delay, Duration::from_secs(30));
+
+	let next_year = SystemTime::now() + Duration::from_secs(365 * 24 * 60 * 60);
+	let delay = policy.delay(0, retry_after(&httpdate::fmt_http_date(next_year)));
+	assert_eq!(delay, Duration::from_secs(30));
+}
+
+#[test]
+fn backs_off_up_to_the_max_delay() {
+	let policy = policy();
+	for attempt in 0..3 {
+		let exponential = Duration::from_millis(500) * 2u32.pow(attempt);
+		let delay = policy.delay(attempt, None);
+		assert!(
+			exponential / 2 <= delay && delay <= exponential,
+			"{delay:?} for attempt {attempt}"
+		);
+	}
+	assert!(policy.delay(20, None) <= Duration::from_secs(30));
+}
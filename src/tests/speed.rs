@@ -0,0 +1,37 @@
+//! Parses speeds which are only partly understood, which keep their text rather than failing the creature.
+use crate::creature::speed::{MovementMode, Speed};
+
+#[test]
+fn speeds_with_unknown_modes_keep_their_text() {
+	let speeds = Speed::parse_all("30 ft., jump 10 ft., swim equal to its walking speed").unwrap();
+	let parsed = speeds
+		.iter()
+		.map(|speed| {
+			(
+				speed.mode(),
+				speed.distance(),
+				speed.raw().map(String::as_str),
+			)
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(
+		parsed,
+		[
+			(Some(MovementMode::Walk), Some(30), None),
+			(None, None, Some("jump 10 ft.")),
+			(None, None, Some("swim equal to its walking speed")),
+		]
+	);
+}
+
+#[test]
+fn speeds_with_unknown_notes_keep_their_distance() {
+	let speeds = Speed::parse_all("20 ft. + 5 ft. per spell level").unwrap();
+	assert_eq!(speeds.len(), 1);
+	assert_eq!(speeds[0].mode(), Some(MovementMode::Walk));
+	assert_eq!(speeds[0].distance(), Some(20));
+	assert_eq!(
+		speeds[0].raw().map(String::as_str),
+		Some("20 ft. + 5 ft. per spell level")
+	);
+}
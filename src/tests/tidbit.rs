@@ -0,0 +1,62 @@
+//! Parses the text of the rows below the ability scores, in the forms which only some creatures use.
+use crate::creature::{
+	challenge_rating::ChallengeRating,
+	tidbit::{Challenge, Senses},
+};
+
+#[test]
+fn challenge_with_alternate_experience() {
+	let challenge = "0 (0 or 10 XP)".parse::<Challenge>().unwrap();
+	assert_eq!(*challenge.rating(), ChallengeRating::Zero);
+	assert_eq!(challenge.experience(), 0);
+	assert_eq!(challenge.alternate_experience(), Some(10));
+
+	let challenge = "5 (1,800 XP)".parse::<Challenge>().unwrap();
+	assert_eq!(challenge.experience(), 1800);
+	assert_eq!(challenge.alternate_experience(), None);
+}
+
+#[test]
+fn whole_challenge_ratings() {
+	assert_eq!(ChallengeRating::whole(0), Some(ChallengeRating::Zero));
+	assert_eq!(ChallengeRating::whole(ChallengeRating::MAX + 1), None);
+	let one = ChallengeRating::whole(1).unwrap();
+	let max = ChallengeRating::whole(ChallengeRating::MAX).unwrap();
+	assert!(ChallengeRating::Half < one && one < max);
+	assert_eq!((one.experience(), one.proficiency_bonus()), (200, 2));
+	assert_eq!((max.experience(), max.proficiency_bonus()), (155000, 9));
+	assert_eq!("30".parse::<ChallengeRating>().unwrap(), max);
+}
+
+#[test]
+fn senses_without_a_range() {
+	let senses = "Blindsight 30 ft. (blind beyond this radius), Tremorsense, Passive Perception 13"
+		.parse::<Senses>()
+		.unwrap();
+	let senses_by_name = senses
+		.senses()
+		.iter()
+		.map(|sense| {
+			(
+				sense.name().as_str(),
+				sense.range(),
+				sense.qualifier().cloned(),
+			)
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(
+		senses_by_name,
+		vec![
+			(
+				"Blindsight",
+				Some(30),
+				Some("blind beyond this radius".to_owned())
+			),
+			("Tremorsense", None, None),
+		]
+	);
+	assert_eq!(senses.passive_perception(), Some(13));
+
+	let senses = "--".parse::<Senses>().unwrap();
+	assert!(senses.senses().is_empty());
+}
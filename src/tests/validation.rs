@@ -0,0 +1,111 @@
+//! Checks the printed values of a creature against the rest of its stat block, as if the page had typos in them.
+use crate::{
+	creature::{ability::Ability, validation::DataWarning, Creature},
+	dndbeyond::creature_list::CreatureListing,
+};
+
+/// Parses a creature's saved page, after replacing some of its text.
+fn parse_with_typos(name: &str, typos: &[(&str, &str)]) -> Creature {
+	let dir = super::fixtures_dir().join("creatures");
+	let listing = std::fs::read_to_string(dir.join(format!("{name}.listing.json"))).unwrap();
+	let listing = serde_json::from_str::<CreatureListing>(&listing).unwrap();
+	let mut html = std::fs::read_to_string(dir.join(format!("{name}.html"))).unwrap();
+	for (text, typo) in typos {
+		assert!(html.contains(text), "{name} has no {text:?}");
+		html = html.replace(text, typo);
+	}
+	Creature::parse(listing, html).unwrap()
+}
+
+/// The field, printed average, and computed average of each warning about an average.
+fn averages(creature: &Creature) -> Vec<(&str, u32, i32)> {
+	creature
+		.warnings()
+		.iter()
+		.filter_map(|warning| match warning {
+			DataWarning::Average {
+				field,
+				printed,
+				computed,
+				..
+			} => Some((field.as_str(), *printed, *computed)),
+			DataWarning::Modifier { .. } => None,
+		})
+		.collect()
+}
+
+#[test]
+fn checks_hit_points_and_attack_damage() {
+	assert!(parse_with_typos("orc", &[]).warnings().is_empty());
+
+	let creature = parse_with_typos(
+		"orc",
+		&[
+			("15\n    </span>", "16\n    </span>"),
+			("6 (1d6 + 3) piercing", "5 (1d6 + 3) piercing"),
+		],
+	);
+	assert_eq!(
+		averages(&creature),
+		[("hit points", 16, 15), ("Javelin damage in Actions", 5, 6)]
+	);
+}
+
+#[test]
+fn checks_damage_outside_of_attacks() {
+	assert!(parse_with_typos("hell-hound", &[]).warnings().is_empty());
+
+	let creature = parse_with_typos(
+		"hell-hound",
+		&[
+			("21 (6d6) fire damage", "24 (6d6) fire damage"),
+			("7 (1d8 + 3) piercing", "8 (1d8 + 3) piercing"),
+		],
+	);
+	// The damage of the attack is only checked once, rather than again in its text.
+	assert_eq!(
+		averages(&creature),
+		[
+			("Bite damage in Actions", 8, 7),
+			("Fire Breath (Recharge 5\u{2013}6) in Actions", 24, 21),
+		]
+	);
+}
+
+#[test]
+fn checks_healing_in_traits() {
+	let creature = parse_with_typos(
+		"will-o-wisp",
+		&[("10 (3d6) hit points", "11 (3d6) hit points")],
+	);
+	assert_eq!(averages(&creature), [("Consume Life in Traits", 11, 10)]);
+}
+
+#[test]
+fn checks_ability_modifiers() {
+	let creature = parse_with_typos(
+		"orc",
+		&[(
+			r#"<span class="ability-block__score">11</span><span class="ability-block__modifier">(+0)</span>"#,
+			r#"<span class="ability-block__score">11</span><span class="ability-block__modifier">(+1)</span>"#,
+		)],
+	);
+	let modifiers = creature
+		.warnings()
+		.iter()
+		.filter(|warning| matches!(warning, DataWarning::Modifier { .. }))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		modifiers,
+		[&DataWarning::Modifier {
+			ability: Ability::Wisdom,
+			score: 11,
+			printed: 1,
+			expected: 0,
+		}]
+	);
+	assert_eq!(
+		modifiers[0].to_string(),
+		"the printed WIS modifier is +1, but a score of 11 gives +0"
+	);
+}
@@ -1,35 +1,209 @@
-pub struct Node(scraper::Selector);
+/// A css selector, along with the text it was parsed from so errors can say which selector failed.
+#[derive(Debug, Clone)]
+pub struct Node {
+	selector: scraper::Selector,
+	text: String,
+}
 impl Node {
-	pub fn new(elements: &[&'static str]) -> Self {
-		Self(scraper::Selector::parse(&elements.join(" > ")).unwrap())
+	pub fn parse(text: &str) -> Result<Self, InvalidSelector> {
+		let selector =
+			scraper::Selector::parse(text).map_err(|_| InvalidSelector(text.to_owned()))?;
+		Ok(Self {
+			selector,
+			text: text.to_owned(),
+		})
 	}
 
-	pub fn apply_to<'a>(
-		&self,
-		element: &scraper::ElementRef<'a>,
-	) -> Result<scraper::ElementRef<'a>, NoSuchElement> {
-		element.select(&self.0).next().ok_or(NoSuchElement)
-	}
-
-	pub fn apply_doc<'a>(
-		&self,
-		element: &'a scraper::Html,
-	) -> Result<scraper::ElementRef<'a>, NoSuchElement> {
-		element.select(&self.0).next().ok_or(NoSuchElement)
+	pub fn as_str(&self) -> &str {
+		&self.text
 	}
 
 	pub fn get_iter<'node, 'html>(
 		&'node self,
 		element: &scraper::ElementRef<'html>,
 	) -> scraper::element_ref::Select<'html, 'node> {
-		element.select(&self.0)
+		element.select(&self.selector)
+	}
+}
+/// Nodes are written either as a whole selector, or as a list of the selectors of each element
+/// from the outermost in, which are joined as direct children (`a > b`).
+impl<'de> serde::Deserialize<'de> for Node {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		#[derive(serde::Deserialize)]
+		#[serde(untagged)]
+		enum Text {
+			Whole(String),
+			Chain(Vec<String>),
+		}
+		let text = match Text::deserialize(deserializer)? {
+			Text::Whole(text) => text,
+			Text::Chain(elements) => elements.join(" > "),
+		};
+		Self::parse(&text).map_err(serde::de::Error::custom)
 	}
 }
 
 #[derive(thiserror::Error, Debug)]
-pub struct NoSuchElement;
-impl std::fmt::Display for NoSuchElement {
+pub struct InvalidSelector(String);
+impl std::fmt::Display for InvalidSelector {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Invalid css selector {:?}", self.0)
+	}
+}
+
+/// An element of a page being parsed, along with the url of the page so that errors can say where they happened.
+#[derive(Clone, Copy)]
+pub struct Scope<'doc> {
+	element: scraper::ElementRef<'doc>,
+	url: &'doc str,
+}
+impl<'doc> Scope<'doc> {
+	/// The whole of a parsed page.
+	pub fn root(document: &'doc scraper::Html, url: &'doc str) -> Self {
+		Self {
+			element: document.root_element(),
+			url,
+		}
+	}
+
+	pub fn element(&self) -> scraper::ElementRef<'doc> {
+		self.element
+	}
+
+	pub fn url(&self) -> &'doc str {
+		self.url
+	}
+
+	/// Returns the first element within this one which matches the selector,
+	/// or an error naming the field (what the element holds) and the selector if there are none.
+	pub fn select(&self, node: &Node, field: &'static str) -> Result<Self, ParseError> {
+		self.select_optional(node)
+			.ok_or_else(|| ParseError::MissingElement {
+				url: self.url.to_owned(),
+				field,
+				selector: node.as_str().to_owned(),
+			})
+	}
+
+	/// Returns the first element within this one which matches the selector, if any.
+	pub fn select_optional(&self, node: &Node) -> Option<Self> {
+		let element = node.get_iter(&self.element).next()?;
+		Some(Self {
+			element,
+			url: self.url,
+		})
+	}
+
+	/// Returns every element within this one which matches the selector.
+	pub fn select_all(&self, node: &Node) -> Vec<Self> {
+		node.get_iter(&self.element)
+			.map(|element| Self {
+				element,
+				url: self.url,
+			})
+			.collect()
+	}
+
+	/// Returns the value of an attribute of this element, or an error naming the field if it is missing.
+	pub fn attr(
+		&self,
+		attribute: &'static str,
+		field: &'static str,
+	) -> Result<&'doc str, ParseError> {
+		self.element
+			.value()
+			.attr(attribute)
+			.ok_or_else(|| ParseError::MissingAttribute {
+				url: self.url.to_owned(),
+				field,
+				attribute,
+			})
+	}
+
+	pub fn inner_html(&self) -> String {
+		self.element.inner_html()
+	}
+
+	/// An error for when the text of a field in this element is not in the expected format.
+	pub fn invalid_text(&self, field: &'static str, text: impl Into<String>) -> ParseError {
+		ParseError::InvalidText {
+			url: self.url.to_owned(),
+			field,
+			text: text.into(),
+		}
+	}
+}
+
+/// A page did not have the structure the scraper expected.
+/// Each error names the field which was being parsed, and the url of the page it was being parsed from.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum ParseError {
+	/// No element matched the selector for a field.
+	MissingElement {
+		url: String,
+		field: &'static str,
+		selector: String,
+	},
+	/// The element for a field did not have an expected attribute.
+	MissingAttribute {
+		url: String,
+		field: &'static str,
+		attribute: &'static str,
+	},
+	/// The text of a field was not in the expected format.
+	InvalidText {
+		url: String,
+		field: &'static str,
+		text: String,
+	},
+}
+impl ParseError {
+	/// The url of the page being parsed.
+	pub fn url(&self) -> &str {
+		match self {
+			Self::MissingElement { url, .. }
+			| Self::MissingAttribute { url, .. }
+			| Self::InvalidText { url, .. } => url,
+		}
+	}
+
+	/// What was being parsed (e.g. `"stat block"` or `"armor class"`).
+	pub fn field(&self) -> &'static str {
+		match self {
+			Self::MissingElement { field, .. }
+			| Self::MissingAttribute { field, .. }
+			| Self::InvalidText { field, .. } => field,
+		}
+	}
+}
+impl std::fmt::Display for ParseError {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "No such html element")
+		match self {
+			Self::MissingElement {
+				url,
+				field,
+				selector,
+			} => write!(
+				f,
+				"Failed to parse {field} of {url:?}: no element matches {selector:?}"
+			),
+			Self::MissingAttribute {
+				url,
+				field,
+				attribute,
+			} => write!(
+				f,
+				"Failed to parse {field} of {url:?}: element has no {attribute:?} attribute"
+			),
+			Self::InvalidText { url, field, text } => {
+				write!(
+					f,
+					"Failed to parse {field} of {url:?}: unexpected text {text:?}"
+				)
+			}
+		}
 	}
 }